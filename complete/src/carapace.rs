@@ -0,0 +1,175 @@
+// For the full copyright and license information, please view the LICENSE
+// file that was distributed with this source code.
+
+use crate::{json::string, Arg, Command, Value, ValueHint};
+
+/// Create a carapace spec
+///
+/// See <https://carapace-sh.github.io/carapace-spec/> for the format. The
+/// strings are written as double quoted YAML strings, which use the same
+/// escaping as JSON.
+///
+/// Carapace only supports a single short and a single long flag per entry,
+/// so the first short and first long flag are grouped and any additional
+/// flags are added as separate entries.
+pub fn render(c: &Command) -> String {
+    let mut flags = String::new();
+    let mut completions = String::new();
+    let indent = " ".repeat(2);
+
+    for arg in c.visible_args() {
+        for key in flag_keys(arg) {
+            flags.push_str(&format!("{indent}{}: {}\n", string(&key), string(arg.help)));
+        }
+
+        let Some(name) = arg.long.first().or(arg.short.first()).map(|f| f.flag) else {
+            continue;
+        };
+        if let Some(values) = arg.value.as_ref().and_then(render_value_hint) {
+            completions.push_str(&format!("{indent}{indent}{}: [{values}]\n", string(name)));
+        }
+    }
+
+    let mut out = format!(
+        "name: {}\ndescription: {}\n",
+        string(c.name),
+        string(c.summary)
+    );
+    if !flags.is_empty() {
+        out.push_str("flags:\n");
+        out.push_str(&flags);
+    }
+    if !completions.is_empty() {
+        out.push_str("completion:\n  flag:\n");
+        out.push_str(&completions);
+    }
+    out
+}
+
+fn flag_keys(arg: &Arg) -> Vec<String> {
+    let mut short = arg.short.iter().map(|f| (format!("-{}", f.flag), &f.value));
+    let mut long = arg.long.iter().map(|f| (format!("--{}", f.flag), &f.value));
+
+    let mut keys = Vec::new();
+    match (short.next(), long.next()) {
+        (Some((s, _)), Some((l, value))) => keys.push(format!("{s}, {l}{}", modifier(value))),
+        (Some((f, value)), None) | (None, Some((f, value))) => {
+            keys.push(format!("{f}{}", modifier(value)))
+        }
+        (None, None) => {}
+    }
+    keys.extend(
+        short
+            .chain(long)
+            .map(|(f, value)| format!("{f}{}", modifier(value))),
+    );
+    keys
+}
+
+fn modifier(value: &Value) -> &'static str {
+    match value {
+        Value::Required(_) => "=",
+        Value::Optional(_) => "?",
+        Value::No => "",
+    }
+}
+
+fn render_value_hint(value: &ValueHint) -> Option<String> {
+    let values = match value {
+        ValueHint::Strings(s) => s.iter().map(|s| string(s)).collect::<Vec<_>>().join(", "),
+        // Carapace separates the value and its description with a tab
+        ValueHint::DescribedStrings(s) => s
+            .iter()
+            .map(|(value, description)| string(&format!("{value}\t{description}")))
+            .collect::<Vec<_>>()
+            .join(", "),
+        ValueHint::AnyPath | ValueHint::FilePath => string("$files"),
+        ValueHint::DirPath => string("$directories"),
+        ValueHint::ExecutablePath | ValueHint::CommandName => string("$executables"),
+        ValueHint::Username => string("$_os.Users"),
+        ValueHint::Hostname => string("$_net.Hosts"),
+        ValueHint::Unknown | ValueHint::Number | ValueHint::DateTime => return None,
+    };
+    Some(values)
+}
+
+#[cfg(test)]
+mod test {
+    use super::render;
+    use crate::{Arg, Command, Flag, Value, ValueHint};
+
+    #[test]
+    fn no_args() {
+        let c = Command {
+            name: "test",
+            summary: "a test",
+            ..Command::default()
+        };
+        assert_eq!(render(&c), "name: \"test\"\ndescription: \"a test\"\n");
+    }
+
+    #[test]
+    fn two_options() {
+        let c = Command {
+            name: "test",
+            summary: "a test",
+            args: vec![
+                Arg {
+                    short: vec![Flag {
+                        flag: "a",
+                        value: Value::No,
+                    }],
+                    long: vec![Flag {
+                        flag: "all",
+                        value: Value::No,
+                    }],
+                    help: "some flag",
+                    value: None,
+                    hidden_help: false,
+                    hidden_complete: false,
+                },
+                Arg {
+                    long: vec![
+                        Flag {
+                            flag: "color",
+                            value: Value::Optional("WHEN"),
+                        },
+                        Flag {
+                            flag: "colour",
+                            value: Value::Optional("WHEN"),
+                        },
+                    ],
+                    help: "colorize",
+                    value: Some(ValueHint::Strings(vec!["always".into(), "never".into()])),
+                    ..Arg::default()
+                },
+                Arg {
+                    short: vec![Flag {
+                        flag: "f",
+                        value: Value::Required("FILE"),
+                    }],
+                    help: "a file",
+                    value: Some(ValueHint::FilePath),
+                    ..Arg::default()
+                },
+            ],
+            ..Command::default()
+        };
+        assert_eq!(
+            render(&c),
+            "\
+name: \"test\"
+description: \"a test\"
+flags:
+  \"-a, --all\": \"some flag\"
+  \"--color?\": \"colorize\"
+  \"--colour?\": \"colorize\"
+  \"-f=\": \"a file\"
+completion:
+  flag:
+    \"color\": [\"always\", \"never\"]
+    \"f\": [\"$files\"]
+"
+        );
+    }
+}
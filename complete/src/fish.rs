@@ -10,7 +10,7 @@ use crate::{Command, Flag, ValueHint};
 pub fn render(c: &Command) -> String {
     let mut out = String::new();
     let name = &c.name;
-    for arg in &c.args {
+    for arg in c.visible_args() {
         let mut line = format!("complete -c {name}");
         for Flag { flag, .. } in &arg.short {
             line.push_str(&format!(" -s {flag}"));
@@ -18,7 +18,7 @@ pub fn render(c: &Command) -> String {
         for Flag { flag, .. } in &arg.long {
             line.push_str(&format!(" -l {flag}"));
         }
-        line.push_str(&format!(" -d '{}'", arg.help));
+        line.push_str(&format!(" -d '{}'", escape_single(arg.help)));
         if let Some(value) = &arg.value {
             line.push_str(&render_value_hint(value));
         }
@@ -34,14 +34,41 @@ fn render_value_hint(value: &ValueHint) -> String {
             let joined = s.join(" ");
             format!(" -f -a \"{joined}\"")
         }
+        ValueHint::DescribedStrings(s) => {
+            let joined = s
+                .iter()
+                .map(|(value, description)| {
+                    // The values are split again by fish, so the quoted
+                    // description has to survive both levels of quoting.
+                    let description = escape_double(&escape_single(description));
+                    format!("{value}\\t'{description}'")
+                })
+                .collect::<Vec<_>>()
+                .join(" ");
+            format!(" -f -a \"{joined}\"")
+        }
         ValueHint::AnyPath | ValueHint::FilePath | ValueHint::ExecutablePath => String::from(" -F"),
         ValueHint::DirPath => " -f -a \"(__fish_complete_directories)\"".into(),
-        ValueHint::Unknown => " -f".into(),
+        ValueHint::CommandName => " -f -a \"(__fish_complete_command)\"".into(),
+        // Dates could be completed with a function here in the future.
+        ValueHint::Unknown | ValueHint::Number | ValueHint::DateTime => " -f".into(),
         ValueHint::Username => " -f -a \"(__fish_complete_users)\"".into(),
         ValueHint::Hostname => " -f -a \"(__fish_print_hostnames)\"".into(),
     }
 }
 
+/// Escape a string for use in single quotes
+fn escape_single(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('\'', "\\'")
+}
+
+/// Escape a string for use in double quotes
+fn escape_double(s: &str) -> String {
+    s.replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('$', "\\$")
+}
+
 #[cfg(test)]
 mod test {
     use super::render;
@@ -81,6 +108,31 @@ mod test {
         assert_eq!(render(&c), "complete -c test -l all -d 'some flag'\n",)
     }
 
+    #[test]
+    fn escaped() {
+        let c = Command {
+            name: "test",
+            args: vec![Arg {
+                long: vec![Flag {
+                    flag: "color",
+                    value: Value::Required("WHEN"),
+                }],
+                help: "don't use \\colors",
+                value: Some(ValueHint::DescribedStrings(vec![(
+                    "never".into(),
+                    "don't use \"$colors\"".into(),
+                )])),
+                ..Arg::default()
+            }],
+            ..Command::default()
+        };
+        assert_eq!(
+            render(&c),
+            "complete -c test -l color -d 'don\\'t use \\\\colors' \
+             -f -a \"never\\t'don\\\\'t use \\\"\\$colors\\\"'\"\n",
+        )
+    }
+
     #[test]
     fn value_hints() {
         let args = [
@@ -88,7 +140,16 @@ mod test {
                 ValueHint::Strings(vec!["all".into(), "none".into()]),
                 "-f -a \"all none\"",
             ),
+            (
+                ValueHint::DescribedStrings(vec![
+                    ("all".into(), "everything".into()),
+                    ("none".into(), "nothing".into()),
+                ]),
+                "-f -a \"all\\t'everything' none\\t'nothing'\"",
+            ),
             (ValueHint::Unknown, "-f"),
+            (ValueHint::Number, "-f"),
+            (ValueHint::DateTime, "-f"),
             (ValueHint::AnyPath, "-F"),
             (ValueHint::FilePath, "-F"),
             (
@@ -96,6 +157,10 @@ mod test {
                 "-f -a \"(__fish_complete_directories)\"",
             ),
             (ValueHint::ExecutablePath, "-F"),
+            (
+                ValueHint::CommandName,
+                "-f -a \"(__fish_complete_command)\"",
+            ),
             (ValueHint::Username, "-f -a \"(__fish_complete_users)\""),
             (ValueHint::Hostname, "-f -a \"(__fish_print_hostnames)\""),
         ];
@@ -110,6 +175,8 @@ mod test {
                     long: vec![],
                     help: "some flag",
                     value: Some(hint),
+                    hidden_help: false,
+                    hidden_complete: false,
                 }],
                 ..Command::default()
             };
@@ -0,0 +1,198 @@
+// For the full copyright and license information, please view the LICENSE
+// file that was distributed with this source code.
+
+use crate::{Arg, Command, Flag, Value, ValueHint};
+
+/// Render the command as JSON for external tooling
+///
+/// The output is a single JSON object with the following schema:
+///
+/// ```text
+/// {
+///   "name": string,
+///   "summary": string,
+///   "version": string,
+///   "after_options": string,
+///   "license": string,
+///   "authors": string,
+///   "args": [{
+///     "short": [flag],
+///     "long": [flag],
+///     "help": string,
+///     "hint": null | {"type": string, "values"?: [string], "descriptions"?: [string]}
+///   }]
+/// }
+/// ```
+///
+/// where a flag is `{"flag": string, "value": null | {"name": string, "required": bool}}`.
+/// If a hint has `descriptions`, it has one for each of its `values`, which
+/// is empty if the value is not documented.
+pub fn render(c: &Command) -> String {
+    let fields = [
+        ("name", string(c.name)),
+        ("summary", string(c.summary)),
+        ("version", string(c.version)),
+        ("after_options", string(c.after_options)),
+        ("license", string(c.license)),
+        ("authors", string(c.authors)),
+        ("args", array(c.visible_args().map(arg))),
+    ];
+    let mut out = object(fields);
+    out.push('\n');
+    out
+}
+
+fn arg(arg: &Arg) -> String {
+    object([
+        ("short", array(arg.short.iter().map(flag))),
+        ("long", array(arg.long.iter().map(flag))),
+        ("help", string(arg.help)),
+        (
+            "hint",
+            arg.value.as_ref().map_or_else(|| "null".into(), hint),
+        ),
+    ])
+}
+
+fn flag(Flag { flag, value }: &Flag) -> String {
+    let value = match value {
+        Value::Required(name) => object([("name", string(name)), ("required", "true".into())]),
+        Value::Optional(name) => object([("name", string(name)), ("required", "false".into())]),
+        Value::No => "null".into(),
+    };
+    object([("flag", string(flag)), ("value", value)])
+}
+
+fn hint(hint: &ValueHint) -> String {
+    let name = match hint {
+        ValueHint::Strings(s) => {
+            return object([
+                ("type", string("strings")),
+                ("values", array(s.iter().map(|s| string(s)))),
+            ])
+        }
+        ValueHint::DescribedStrings(s) => {
+            return object([
+                ("type", string("strings")),
+                ("values", array(s.iter().map(|(value, _)| string(value)))),
+                (
+                    "descriptions",
+                    array(s.iter().map(|(_, description)| string(description))),
+                ),
+            ])
+        }
+        ValueHint::Unknown => "unknown",
+        ValueHint::Number => "number",
+        ValueHint::DateTime => "date_time",
+        ValueHint::AnyPath => "any_path",
+        ValueHint::FilePath => "file_path",
+        ValueHint::DirPath => "dir_path",
+        ValueHint::ExecutablePath => "executable_path",
+        ValueHint::CommandName => "command_name",
+        ValueHint::Username => "username",
+        ValueHint::Hostname => "hostname",
+    };
+    object([("type", string(name))])
+}
+
+fn object<'a>(fields: impl IntoIterator<Item = (&'a str, String)>) -> String {
+    let fields: Vec<_> = fields
+        .into_iter()
+        .map(|(key, value)| format!("{}:{value}", string(key)))
+        .collect();
+    format!("{{{}}}", fields.join(","))
+}
+
+fn array(items: impl IntoIterator<Item = String>) -> String {
+    let items: Vec<_> = items.into_iter().collect();
+    format!("[{}]", items.join(","))
+}
+
+pub(crate) fn string(s: &str) -> String {
+    let mut out = String::from('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c.is_control() => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+#[cfg(test)]
+mod test {
+    use super::{render, string};
+    use crate::{Arg, Command, Flag, Value, ValueHint};
+
+    #[test]
+    fn escape() {
+        assert_eq!(string("a \"b\"\n\\c\u{1}"), r#""a \"b\"\n\\c\u0001""#);
+    }
+
+    #[test]
+    fn empty() {
+        let c = Command {
+            name: "test",
+            ..Command::default()
+        };
+        assert_eq!(
+            render(&c),
+            r#"{"name":"test","summary":"","version":"","after_options":"","license":"","authors":"","args":[]}"#
+                .to_string()
+                + "\n"
+        );
+    }
+
+    #[test]
+    fn args() {
+        let c = Command {
+            name: "test",
+            args: vec![
+                Arg {
+                    short: vec![Flag {
+                        flag: "a",
+                        value: Value::No,
+                    }],
+                    long: vec![Flag {
+                        flag: "all",
+                        value: Value::No,
+                    }],
+                    help: "some flag",
+                    value: None,
+                    hidden_help: false,
+                    hidden_complete: false,
+                },
+                Arg {
+                    long: vec![Flag {
+                        flag: "color",
+                        value: Value::Optional("WHEN"),
+                    }],
+                    help: "colorize",
+                    value: Some(ValueHint::Strings(vec!["always".into(), "never".into()])),
+                    ..Arg::default()
+                },
+                Arg {
+                    short: vec![Flag {
+                        flag: "f",
+                        value: Value::Required("FILE"),
+                    }],
+                    value: Some(ValueHint::FilePath),
+                    ..Arg::default()
+                },
+            ],
+            ..Command::default()
+        };
+        let expected = [
+            r#"{"short":[{"flag":"a","value":null}],"long":[{"flag":"all","value":null}],"help":"some flag","hint":null}"#,
+            r#"{"short":[],"long":[{"flag":"color","value":{"name":"WHEN","required":false}}],"help":"colorize","hint":{"type":"strings","values":["always","never"]}}"#,
+            r#"{"short":[{"flag":"f","value":{"name":"FILE","required":true}}],"long":[],"help":"","hint":{"type":"file_path"}}"#,
+        ];
+        assert!(render(&c).contains(&format!(r#""args":[{}]"#, expected.join(","))));
+    }
+}
@@ -13,10 +13,33 @@
 //!  - Some information is removed because it is irrelevant for completion and documentation
 //!  - This struct is meant to exist at runtime of the program
 //!
+//! For commands that are not defined with the derive macros, the
+//! [`Command`] can be constructed with builder methods:
+//!
+//! ```
+//! use uutils_args_complete::{render, Arg, Command, Value, ValueHint};
+//!
+//! let command = Command::new("ls")
+//!     .summary("List directory contents")
+//!     .arg(Arg::new().short("a").long("all").help("Do not ignore hidden files"))
+//!     .arg(
+//!         Arg::new()
+//!             .long_with_value("color", Value::Optional("WHEN"))
+//!             .help("Colorize the output")
+//!             .hint(ValueHint::Strings(vec!["always".into(), "never".into()])),
+//!     )
+//!     .operand("[FILE]...");
+//! let fish = render(&command, "fish").unwrap();
+//! assert!(fish.contains("-s a -l all"));
+//! ```
+//!
+mod carapace;
 mod fish;
+mod json;
 mod man;
 mod md;
 mod nu;
+mod sh;
 mod zsh;
 
 /// A description of a CLI command
@@ -29,6 +52,8 @@ pub struct Command<'a> {
     pub version: &'a str,
     pub after_options: &'a str,
     pub args: Vec<Arg<'a>>,
+    /// The operands of the command in their usage form, e.g. `[FILE]...`
+    pub operands: Vec<&'a str>,
     pub license: &'a str,
     pub authors: &'a str,
 }
@@ -43,6 +68,12 @@ pub struct Arg<'a> {
     pub long: Vec<Flag<'a>>,
     pub help: &'a str,
     pub value: Option<ValueHint>,
+    /// Whether the argument is left out of the documentation, like the
+    /// manpage and markdown
+    pub hidden_help: bool,
+    /// Whether the argument is left out of the completions and other
+    /// formats for tooling
+    pub hidden_complete: bool,
 }
 
 pub struct Flag<'a> {
@@ -50,6 +81,114 @@ pub struct Flag<'a> {
     pub value: Value<'a>,
 }
 
+impl<'a> Command<'a> {
+    /// Create a command with the given name and no arguments
+    pub fn new(name: &'a str) -> Self {
+        Self {
+            name,
+            ..Self::default()
+        }
+    }
+
+    pub fn summary(mut self, summary: &'a str) -> Self {
+        self.summary = summary;
+        self
+    }
+
+    pub fn version(mut self, version: &'a str) -> Self {
+        self.version = version;
+        self
+    }
+
+    pub fn after_options(mut self, after_options: &'a str) -> Self {
+        self.after_options = after_options;
+        self
+    }
+
+    pub fn license(mut self, license: &'a str) -> Self {
+        self.license = license;
+        self
+    }
+
+    pub fn authors(mut self, authors: &'a str) -> Self {
+        self.authors = authors;
+        self
+    }
+
+    /// Add an argument
+    pub fn arg(mut self, arg: Arg<'a>) -> Self {
+        self.args.push(arg);
+        self
+    }
+
+    /// The arguments that are not hidden from the completions
+    pub(crate) fn visible_args(&self) -> impl Iterator<Item = &Arg<'a>> {
+        self.args.iter().filter(|arg| !arg.hidden_complete)
+    }
+
+    /// The arguments that are not hidden from the documentation
+    pub(crate) fn documented_args(&self) -> impl Iterator<Item = &Arg<'a>> {
+        self.args.iter().filter(|arg| !arg.hidden_help)
+    }
+
+    /// Add an operand in its usage form, e.g. `[FILE]...`
+    pub fn operand(mut self, operand: &'a str) -> Self {
+        self.operands.push(operand);
+        self
+    }
+}
+
+impl<'a> Arg<'a> {
+    /// Create an argument without any flags
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a short flag that does not take a value
+    ///
+    /// The flag is given without the leading `-`.
+    pub fn short(self, flag: &'a str) -> Self {
+        self.short_with_value(flag, Value::No)
+    }
+
+    /// Add a short flag with the given value
+    pub fn short_with_value(mut self, flag: &'a str, value: Value<'a>) -> Self {
+        self.short.push(Flag { flag, value });
+        self
+    }
+
+    /// Add a long flag that does not take a value
+    ///
+    /// The flag is given without the leading `--`.
+    pub fn long(self, flag: &'a str) -> Self {
+        self.long_with_value(flag, Value::No)
+    }
+
+    /// Add a long flag with the given value
+    pub fn long_with_value(mut self, flag: &'a str, value: Value<'a>) -> Self {
+        self.long.push(Flag { flag, value });
+        self
+    }
+
+    pub fn help(mut self, help: &'a str) -> Self {
+        self.help = help;
+        self
+    }
+
+    /// Set the hint for the value of the flags
+    pub fn hint(mut self, hint: ValueHint) -> Self {
+        self.value = Some(hint);
+        self
+    }
+
+    /// Hide the argument from completions and documentation
+    pub fn hidden(mut self) -> Self {
+        self.hidden_help = true;
+        self.hidden_complete = true;
+        self
+    }
+}
+
 pub enum Value<'a> {
     Required(&'a str),
     Optional(&'a str),
@@ -59,23 +198,145 @@ pub enum Value<'a> {
 // Modelled after claps ValueHint
 pub enum ValueHint {
     Strings(Vec<String>),
+    /// Strings with a description for each of them
+    ///
+    /// Shells that support it show the description next to the string.
+    /// The others complete the strings like [`ValueHint::Strings`].
+    DescribedStrings(Vec<(String, String)>),
     Unknown,
+    /// A number, for which no completions are offered
+    Number,
+    /// A date or time, for which no completions are offered yet
+    ///
+    /// This at least keeps shells from suggesting files for it.
+    DateTime,
     AnyPath,
     FilePath,
     DirPath,
     ExecutablePath,
+    /// The name of a command in `$PATH`
+    CommandName,
     Username,
     Hostname,
 }
 
-pub fn render(c: &Command, shell: &str) -> String {
-    match shell {
+/// The formats that [`render`] can generate
+pub const FORMATS: &[&str] = &["md", "fish", "zsh", "nu", "sh", "man", "json", "carapace"];
+
+/// Error returned by [`render`] if the requested format cannot be generated
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// The shell is known, but generating completions for it is not supported yet
+    Unsupported(String),
+    /// The format is not known at all
+    Unknown(String),
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Unsupported(shell) => write!(f, "completion for '{shell}' is not supported yet")?,
+            Self::Unknown(shell) => write!(f, "unknown completion format '{shell}'")?,
+        }
+        write!(f, ", expected one of: {}", FORMATS.join(", "))
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// Render the command in the given format
+///
+/// See [`FORMATS`] for the accepted formats. The `nushell` alias is also
+/// accepted for `nu`.
+pub fn render(c: &Command, shell: &str) -> Result<String, Error> {
+    Ok(match shell {
         "md" => md::render(c),
         "fish" => fish::render(c),
         "zsh" => zsh::render(c),
         "nu" | "nushell" => nu::render(c),
+        "sh" => sh::render(c),
         "man" => man::render(c),
-        "sh" | "bash" | "csh" | "elvish" | "powershell" => panic!("shell '{shell}' completion is not implemented yet!"),
-        _ => panic!("unknown option '{shell}'! Expected one of: \"md\", \"fish\", \"zsh\", \"man\", \"sh\", \"bash\", \"csh\", \"elvish\", \"powershell\""),
+        "json" => json::render(c),
+        "carapace" => carapace::render(c),
+        "bash" | "csh" | "elvish" | "powershell" => return Err(Error::Unsupported(shell.into())),
+        _ => return Err(Error::Unknown(shell.into())),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::{render, Arg, Command, Error, Flag, Value, ValueHint};
+
+    #[test]
+    fn builder() {
+        let built = Command::new("test")
+            .summary("A test command")
+            .version("1.0")
+            .arg(Arg::new().short("a").long("all").help("Show all"))
+            .arg(
+                Arg::new()
+                    .short_with_value("n", Value::Required("N"))
+                    .hint(ValueHint::Number),
+            )
+            .operand("[FILE]...");
+
+        let manual = Command {
+            name: "test",
+            summary: "A test command",
+            version: "1.0",
+            args: vec![
+                Arg {
+                    short: vec![Flag {
+                        flag: "a",
+                        value: Value::No,
+                    }],
+                    long: vec![Flag {
+                        flag: "all",
+                        value: Value::No,
+                    }],
+                    help: "Show all",
+                    value: None,
+                    hidden_help: false,
+                    hidden_complete: false,
+                },
+                Arg {
+                    short: vec![Flag {
+                        flag: "n",
+                        value: Value::Required("N"),
+                    }],
+                    value: Some(ValueHint::Number),
+                    ..Arg::default()
+                },
+            ],
+            operands: vec!["[FILE]..."],
+            ..Command::default()
+        };
+
+        for shell in ["fish", "zsh", "md", "man", "json"] {
+            assert_eq!(render(&built, shell), render(&manual, shell));
+        }
+    }
+
+    #[test]
+    fn hidden_args() {
+        let c = Command::new("test")
+            .arg(Arg::new().long("visible").help("A visible flag"))
+            .arg(Arg::new().long("secret").help("A hidden flag").hidden());
+
+        for shell in ["fish", "zsh", "nu", "sh", "md", "man", "json", "carapace"] {
+            let out = render(&c, shell).unwrap();
+            assert!(out.contains("visible"), "{shell}");
+            assert!(!out.contains("secret"), "{shell}");
+        }
+    }
+
+    #[test]
+    fn unsupported() {
+        let c = Command::new("test");
+        assert_eq!(render(&c, "bash"), Err(Error::Unsupported("bash".into())));
+        assert_eq!(render(&c, "foo"), Err(Error::Unknown("foo".into())));
+        let msg = Error::Unsupported("csh".into()).to_string();
+        assert!(msg.contains("'csh' is not supported"));
+        assert!(msg.contains("fish, zsh"));
     }
 }
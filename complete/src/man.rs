@@ -2,18 +2,20 @@
 // file that was distributed with this source code.
 
 use crate::{Command, Flag, Value};
-use roff::{bold, italic, roman, Roff};
+use roff::{bold, italic, line_break, roman, Inline, Roff};
 
 pub fn render(c: &Command) -> String {
     let mut page = Roff::new();
     page.control("TH", [&c.name.to_uppercase(), "1"]);
     page.control("SH", ["NAME"]);
     page.text([roman(c.name)]);
+    page.control("SH", ["SYNOPSIS"]);
+    page.text(synopsis(c));
     page.control("SH", ["DESCRIPTION"]);
     page.text([roman(c.summary)]);
     page.control("SH", ["OPTIONS"]);
 
-    for arg in &c.args {
+    for arg in c.documented_args() {
         page.control("TP", []);
 
         let mut flags = Vec::new();
@@ -55,7 +57,12 @@ pub fn render(c: &Command) -> String {
             }
         }
         page.text(flags);
-        page.text([roman(arg.help)]);
+        page.text(markdown(arg.help));
+    }
+
+    if !c.after_options.is_empty() {
+        page.control("SH", ["EXAMPLES"]);
+        page.text([roman(c.after_options)]);
     }
 
     page.control("SH", ["AUTHORS"]);
@@ -66,3 +73,121 @@ pub fn render(c: &Command) -> String {
     page.text([roman(format!("License: {}", &c.license))]);
     page.render()
 }
+
+fn synopsis(c: &Command) -> Vec<Inline> {
+    let mut synopsis = vec![bold(c.name), roman(" ["), italic("OPTION"), roman("]...")];
+    for operand in &c.operands {
+        synopsis.push(roman(format!(" {operand}")));
+    }
+    synopsis
+}
+
+/// Convert a help string written in a subset of markdown to roff
+///
+/// Text between backticks or `**` is rendered in bold and text between `*`
+/// in italics. Every line of the help string is put on a separate line.
+fn markdown(text: &str) -> Vec<Inline> {
+    let mut out = Vec::new();
+    for (i, line) in text.lines().enumerate() {
+        if i > 0 {
+            out.push(line_break());
+        }
+        markdown_line(line, &mut out);
+    }
+    out
+}
+
+fn markdown_line(line: &str, out: &mut Vec<Inline>) {
+    let mut text = String::new();
+    let mut rest = line;
+    while let Some(c) = rest.chars().next() {
+        let (delimiter, is_bold) = if rest.starts_with("**") {
+            ("**", true)
+        } else if c == '`' {
+            ("`", true)
+        } else if c == '*' {
+            ("*", false)
+        } else {
+            text.push(c);
+            rest = &rest[c.len_utf8()..];
+            continue;
+        };
+
+        let inner = &rest[delimiter.len()..];
+        match inner.find(delimiter) {
+            Some(end) if end > 0 => {
+                if !text.is_empty() {
+                    out.push(roman(std::mem::take(&mut text)));
+                }
+                let styled = &inner[..end];
+                out.push(if is_bold {
+                    bold(styled)
+                } else {
+                    italic(styled)
+                });
+                rest = &inner[end + delimiter.len()..];
+            }
+            // Unmatched delimiters are just regular text
+            _ => {
+                text.push_str(delimiter);
+                rest = inner;
+            }
+        }
+    }
+    if !text.is_empty() {
+        out.push(roman(text));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{markdown, render};
+    use crate::Command;
+
+    #[test]
+    fn help_markdown() {
+        let render_help = |help: &str| {
+            let mut page = roff::Roff::new();
+            page.text(markdown(help));
+            page.to_roff()
+        };
+        assert_eq!(render_help("plain text"), "plain text\n");
+        assert_eq!(
+            render_help("an *emphasized* word"),
+            "an \\fIemphasized\\fR word\n"
+        );
+        assert_eq!(
+            render_help("use `--foo` or **--bar**"),
+            "use \\fB\\-\\-foo\\fR or \\fB\\-\\-bar\\fR\n"
+        );
+        assert_eq!(render_help("2 * 3 = 6"), "2 * 3 = 6\n");
+        assert_eq!(render_help("line 1\nline 2"), "line 1\n.br\nline 2\n");
+    }
+
+    #[test]
+    fn synopsis() {
+        let c = Command {
+            name: "test",
+            operands: vec!["SOURCE...", "DEST"],
+            ..Command::default()
+        };
+        let page = render(&c);
+        assert!(page.contains(".SH SYNOPSIS\n\\fBtest\\fR [\\fIOPTION\\fR]... SOURCE... DEST\n"));
+    }
+
+    #[test]
+    fn examples() {
+        let c = Command {
+            name: "test",
+            ..Command::default()
+        };
+        assert!(!render(&c).contains(".SH EXAMPLES"));
+
+        let c = Command {
+            name: "test",
+            after_options: "test foo",
+            ..Command::default()
+        };
+        assert!(render(&c).contains(".SH EXAMPLES\ntest foo\n"));
+    }
+}
@@ -35,7 +35,7 @@ fn additional(c: &Command) -> String {
 fn options(c: &Command) -> String {
     let mut out = String::from("## Options\n\n");
     out.push_str("<dl>\n");
-    for arg in &c.args {
+    for arg in c.documented_args() {
         out.push_str("<dt>");
 
         let mut flags = Vec::new();
@@ -11,7 +11,7 @@ pub fn render(c: &Command) -> String {
     let mut complete_commands = Vec::new();
     let indent = " ".repeat(4);
 
-    for arg in &c.args {
+    for arg in c.visible_args() {
         let hint = if let Some((cmd, hint_name)) = render_completion_command(command_name, arg) {
             complete_commands.push(cmd);
             hint_name
@@ -68,18 +68,37 @@ fn render_value_hint(value: &ValueHint) -> Option<String> {
                 .join(", ");
             Some(format!("[{vals}]"))
         }
+        ValueHint::DescribedStrings(s) => {
+            let vals = s
+                .iter()
+                .map(|(value, description)| {
+                    let description = escape_double(description);
+                    format!("{{value: \"{value}\", description: \"{description}\"}}")
+                })
+                .collect::<Vec<_>>()
+                .join(", ");
+            Some(format!("[{vals}]"))
+        }
         // The path arguments could be improved, but nu currently does not give
         // us enough context to improve the default completions.
         ValueHint::Unknown
+        | ValueHint::Number
+        | ValueHint::DateTime
         | ValueHint::AnyPath
         | ValueHint::FilePath
         | ValueHint::ExecutablePath
+        | ValueHint::CommandName
         | ValueHint::DirPath
         | ValueHint::Username
         | ValueHint::Hostname => None,
     }
 }
 
+/// Escape a string for use in double quotes
+fn escape_double(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
 fn template(name: &str, complete_commands: &str, args: &str) -> String {
     format!("{complete_commands}\n\nexport extern \"{name}\" [\n{args}]\n")
 }
@@ -0,0 +1,56 @@
+// For the full copyright and license information, please view the LICENSE
+// file that was distributed with this source code.
+
+use crate::{Command, Flag};
+
+/// Create completion script for POSIX `sh`
+///
+/// POSIX does not specify programmable completion, so shells like dash and
+/// ash do not offer any. The script therefore only registers a word list with
+/// the bash-compatible `complete` builtin if the shell has one, as is the
+/// case for bash running as `sh`. Other shells skip it without errors.
+///
+/// Because a word list cannot depend on the previous word, values of options
+/// are not completed and the shell falls back to completing file names.
+pub fn render(c: &Command) -> String {
+    let mut words = Vec::new();
+    for arg in c.visible_args() {
+        for Flag { flag, .. } in &arg.short {
+            words.push(format!("-{flag}"));
+        }
+        for Flag { flag, .. } in &arg.long {
+            words.push(format!("--{flag}"));
+        }
+    }
+    let words = words.join(" ");
+    let name = c.name;
+    format!(
+        "\
+if command -v complete >/dev/null 2>&1; then
+    complete -o default -W '{words}' {name}
+fi
+"
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::render;
+    use crate::{Arg, Command, Value};
+
+    #[test]
+    fn flags() {
+        let c = Command::new("test")
+            .arg(Arg::new().short("a").long("all"))
+            .arg(Arg::new().long_with_value("color", Value::Optional("WHEN")))
+            .arg(Arg::new().long("secret").hidden());
+        assert_eq!(
+            render(&c),
+            "\
+if command -v complete >/dev/null 2>&1; then
+    complete -o default -W '-a --all --color' test
+fi
+"
+        );
+    }
+}
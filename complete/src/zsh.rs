@@ -1,21 +1,21 @@
 // For the full copyright and license information, please view the LICENSE
 // file that was distributed with this source code.
 
-use crate::{Arg, Command, Flag, Value, ValueHint};
+use crate::{Command, Flag, Value, ValueHint};
 
 /// Create completion script for `zsh`
 pub fn render(c: &Command) -> String {
-    template(c.name, &render_args(&c.args))
+    template(c.name, &render_args(c))
 }
 
-fn render_args(args: &[Arg]) -> String {
+fn render_args(c: &Command) -> String {
     let mut out = String::new();
     let indent = " ".repeat(8);
 
     // The reference for this can be found here:
     // https://zsh.sourceforge.io/Doc/Release/Completion-System.html#Completion-System
-    for arg in args {
-        let help = &arg.help;
+    for arg in c.visible_args() {
+        let help = escape_help(arg.help);
         let hint = arg
             .value
             .as_ref()
@@ -31,7 +31,7 @@ fn render_args(args: &[Arg]) -> String {
                 Value::Optional(name) => format!("-{flag}-[{help}]::{name}:{hint}"),
                 Value::No => format!("-{flag}[{help}]"),
             };
-            out.push_str(&format!("{indent}'{s}'\\\n"));
+            out.push_str(&format!("{indent}'{}'\\\n", escape_single(&s)));
         }
         for Flag { flag, value } in &arg.long {
             let s = match value {
@@ -43,7 +43,7 @@ fn render_args(args: &[Arg]) -> String {
                 Value::Optional(name) => format!("--{flag}=-[{help}]::{name}:{hint}"),
                 Value::No => format!("--{flag}[{help}]"),
             };
-            out.push_str(&format!("{indent}'{s}' \\\n"));
+            out.push_str(&format!("{indent}'{}' \\\n", escape_single(&s)));
         }
     }
     out
@@ -55,15 +55,47 @@ fn render_value_hint(value: &ValueHint) -> String {
             let joined = s.join(" ");
             format!("({joined})")
         }
+        ValueHint::DescribedStrings(s) => {
+            let joined = s
+                .iter()
+                .map(|(value, description)| format!("{value}\\:\"{}\"", escape_double(description)))
+                .collect::<Vec<_>>()
+                .join(" ");
+            format!("(({joined}))")
+        }
         ValueHint::Unknown => "".into(),
+        // A single space tells zsh that there are no completions.
+        // Dates could be completed with a function here in the future.
+        ValueHint::Number | ValueHint::DateTime => " ".into(),
         ValueHint::AnyPath | ValueHint::FilePath => "_files".into(),
         ValueHint::ExecutablePath => "_absolute_command_paths".into(),
+        ValueHint::CommandName => "_command_names".into(),
         ValueHint::DirPath => "_directories".into(),
         ValueHint::Username => "_users".into(),
         ValueHint::Hostname => "_hosts".into(),
     }
 }
 
+/// Escape the explanation of an option, which is in brackets
+fn escape_help(s: &str) -> String {
+    s.replace('\\', "\\\\")
+        .replace('[', "\\[")
+        .replace(']', "\\]")
+}
+
+/// Escape a string for use in double quotes
+fn escape_double(s: &str) -> String {
+    s.replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('$', "\\$")
+        .replace('`', "\\`")
+}
+
+/// Escape a string for use in single quotes
+fn escape_single(s: &str) -> String {
+    s.replace('\'', "'\\''")
+}
+
 fn template(name: &str, args: &str) -> String {
     format!(
         "\
@@ -2,12 +2,13 @@
 // file that was distributed with this source code.
 
 use proc_macro2::TokenStream;
-use quote::quote;
+use quote::{quote, quote_spanned};
 use syn::{Attribute, Fields, FieldsUnnamed, Ident, Meta, Variant};
 
 use crate::{
     attributes::{ArgAttr, ArgumentsAttr},
     flags::{Flags, Value},
+    help::read_help_section,
 };
 
 pub struct Argument {
@@ -17,16 +18,40 @@ pub struct Argument {
     pub help: String,
 }
 
+#[allow(clippy::large_enum_variant)]
 pub enum ArgType {
     Option {
         flags: Flags,
-        hidden: bool,
+        /// Whether the option is left out of the help
+        hidden_help: bool,
+        /// Whether the option is left out of the completions
+        hidden_complete: bool,
         takes_value: bool,
         default: TokenStream,
+        deprecated: Option<String>,
+        /// The number of values this option takes
+        nargs: usize,
+        /// The function that parses the values instead of `Value`
+        parser: Option<TokenStream>,
+        /// The range that the values must be in
+        range: Option<TokenStream>,
+        /// The message for values that fail to parse, from `error = "..."`
+        error: Option<String>,
+        /// Whether a separate value may not start with a hyphen
+        no_dash_value: bool,
+        /// Whether the option takes all remaining arguments as values
+        greedy: bool,
+        /// Whether all arguments after this option are operands
+        terminator: bool,
     },
     Free {
         filters: Vec<syn::Ident>,
     },
+    Subcommand {
+        name: String,
+    },
+    /// All operands, collected after the options are parsed
+    Operands,
 }
 
 pub fn parse_arguments_attr(attrs: &[Attribute]) -> ArgumentsAttr {
@@ -38,7 +63,7 @@ pub fn parse_arguments_attr(attrs: &[Attribute]) -> ArgumentsAttr {
     ArgumentsAttr::default()
 }
 
-pub fn parse_argument(v: Variant) -> Vec<Argument> {
+pub fn parse_argument(v: Variant, arguments_attr: &ArgumentsAttr) -> Vec<Argument> {
     let ident = v.ident;
     let attributes = get_arg_attributes(&v.attrs).unwrap();
 
@@ -78,16 +103,60 @@ pub fn parse_argument(v: Variant) -> Vec<Argument> {
                     if let Some(help) = opt.help {
                         arg_help = help;
                     }
+                    if let Some(section) = opt.help_section {
+                        let Some(file) = &arguments_attr.file else {
+                            panic!("help_section = \"{section}\" requires a help file, set with #[arguments(file = ...)]");
+                        };
+                        arg_help = read_help_section(file, arguments_attr.file_base, &section);
+                    }
+                    assert!(
+                        opt.flags.plus.is_empty() || field.is_some(),
+                        "A plus-prefixed argument must have a field for the value"
+                    );
+                    let nargs = opt.nargs.unwrap_or(1);
+                    assert!(
+                        nargs == 1 || field.is_some(),
+                        "An option with nargs must have a field for the values"
+                    );
+                    assert!(
+                        !opt.greedy || (nargs == 1 && field.is_some()),
+                        "A greedy option must have a field for the values and cannot have nargs"
+                    );
                     ArgType::Option {
                         flags: opt.flags,
                         takes_value: field.is_some(),
                         default: default_expr,
-                        hidden: opt.hidden,
+                        hidden_help: opt.hidden_help,
+                        hidden_complete: opt.hidden_complete,
+                        deprecated: opt.deprecated,
+                        parser: opt.parser.map(|parser| quote!(#parser)),
+                        range: opt.range.map(|range| quote!(#range)),
+                        error: opt.error,
+                        no_dash_value: opt.no_dash_value,
+                        greedy: opt.greedy,
+                        terminator: opt.terminator,
+                        nargs,
                     }
                 }
                 ArgAttr::Free(free) => ArgType::Free {
                     filters: free.filters,
                 },
+                ArgAttr::Subcommand(sub) => {
+                    assert!(
+                        field.is_some(),
+                        "A subcommand variant must have a field with the arguments of the subcommand"
+                    );
+                    ArgType::Subcommand {
+                        name: sub.name.unwrap_or_else(|| ident.to_string().to_lowercase()),
+                    }
+                }
+                ArgAttr::Operands => {
+                    assert!(
+                        field.is_some(),
+                        "An operands variant must have a field for the operands"
+                    );
+                    ArgType::Operands
+                }
             };
             Argument {
                 ident: ident.clone(),
@@ -99,7 +168,7 @@ pub fn parse_argument(v: Variant) -> Vec<Argument> {
         .collect()
 }
 
-fn collect_help(attrs: &[Attribute]) -> String {
+pub fn collect_help(attrs: &[Attribute]) -> String {
     let mut help = Vec::new();
     for attr in attrs {
         if attr.path().is_ident("doc") {
@@ -122,6 +191,15 @@ fn collect_help(attrs: &[Attribute]) -> String {
 }
 
 fn get_arg_attributes(attrs: &[Attribute]) -> syn::Result<Vec<ArgAttr>> {
+    // The `option` attribute was renamed to `arg`, so we give a helpful
+    // error instead of silently ignoring it.
+    if let Some(attr) = attrs.iter().find(|a| a.path().is_ident("option")) {
+        return Err(syn::Error::new_spanned(
+            attr,
+            "the `option` attribute has been renamed, use `#[arg(...)]` instead",
+        ));
+    }
+
     attrs
         .iter()
         .filter(|a| a.path().is_ident("arg"))
@@ -129,20 +207,86 @@ fn get_arg_attributes(attrs: &[Attribute]) -> syn::Result<Vec<ArgAttr>> {
         .collect()
 }
 
-pub fn short_handling(args: &[Argument]) -> (TokenStream, Vec<char>) {
+/// Emit a compile error for every flag that is defined more than once
+pub fn check_duplicate_flags(args: &[Argument]) -> TokenStream {
+    let mut seen: Vec<(String, &Ident)> = Vec::new();
+    let mut errors = Vec::new();
+
+    for arg in args {
+        let ArgType::Option { flags, .. } = &arg.arg_type else {
+            continue;
+        };
+        let all_flags = flags
+            .short
+            .iter()
+            .map(|f| format!("-{}", f.flag))
+            .chain(flags.long.iter().map(|f| format!("--{}", f.flag)))
+            .chain(flags.dd_style.iter().map(|(f, _)| format!("{f}=")));
+
+        for flag in all_flags {
+            match seen.iter().find(|(f, _)| *f == flag) {
+                Some((_, first)) => {
+                    let msg = if *first == &arg.ident {
+                        format!("flag '{flag}' is defined multiple times by `{first}`")
+                    } else {
+                        format!(
+                            "flag '{flag}' is defined by both `{first}` and `{}`",
+                            arg.ident
+                        )
+                    };
+                    errors.push(quote_spanned!(arg.ident.span() => compile_error!(#msg);));
+                }
+                None => seen.push((flag, &arg.ident)),
+            }
+        }
+    }
+
+    quote!(#(#errors)*)
+}
+
+pub fn short_handling(args: &[Argument], attr: &ArgumentsAttr) -> (TokenStream, Vec<char>) {
     let mut match_arms = Vec::new();
     let mut short_flags = Vec::new();
 
+    // All flags are collected to suggest alternatives for unknown flags.
+    let mut all_short_flags: Vec<_> = attr.help_flags.short.iter().map(|f| f.flag).collect();
+    let mut all_long_flags: Vec<_> = attr
+        .help_flags
+        .long
+        .iter()
+        .map(|f| f.flag.clone())
+        .collect();
+
     for arg in args {
-        let (flags, takes_value, default) = match arg.arg_type {
-            ArgType::Option {
-                ref flags,
-                takes_value,
-                ref default,
-                hidden: _,
-            } => (flags, takes_value, default),
-            ArgType::Free { .. } => continue,
-        };
+        let (flags, takes_value, default, deprecated, nargs, greedy, terminator) =
+            match arg.arg_type {
+                ArgType::Option {
+                    ref flags,
+                    takes_value,
+                    ref default,
+                    ref deprecated,
+                    nargs,
+                    hidden_help: _,
+                    hidden_complete: _,
+                    parser: _,
+                    range: _,
+                    error: _,
+                    no_dash_value: _,
+                    greedy,
+                    terminator,
+                } => (
+                    flags,
+                    takes_value,
+                    default,
+                    deprecated,
+                    nargs,
+                    greedy,
+                    terminator,
+                ),
+                ArgType::Free { .. } | ArgType::Subcommand { .. } | ArgType::Operands => continue,
+            };
+
+        all_long_flags.extend(flags.long.iter().map(|f| f.flag.clone()));
 
         if flags.short.is_empty() {
             continue;
@@ -155,43 +299,95 @@ pub fn short_handling(args: &[Argument]) -> (TokenStream, Vec<char>) {
                 (_, false) => {
                     panic!("Option cannot take a value if the variant doesn't have a field")
                 }
+                (Value::No | Value::Optional(_), true) if nargs != 1 => {
+                    panic!("An option with nargs must have flags with a required value")
+                }
+                (Value::No | Value::Optional(_), true) if greedy => {
+                    panic!("A greedy option must have flags with a required value")
+                }
                 (Value::No, true) => default_value_expression(&arg.ident, default),
-                (Value::Optional(_), true) => optional_value_expression(&arg.ident, default),
-                (Value::Required(_), true) => required_value_expression(&arg.ident),
+                (Value::Optional(_), true) => optional_value_expression(arg, default),
+                (Value::Required(_), true) => required_value_expression(arg, nargs),
             };
-            match_arms.push(quote!(#pat => { #expr }));
+            let warning = deprecation_warning(deprecated);
+            let expr = terminator_expression(expr, terminator);
+            match_arms.push(quote!(#pat => { #warning #expr }));
             short_flags.push(pat);
         }
     }
 
+    all_short_flags.extend(&short_flags);
+    let suggestion_threshold = attr.suggestion_threshold;
+
+    let unknown = if attr.passthrough_unknown {
+        quote!(return Ok(Some(Argument::Unknown(option.into()))))
+    } else {
+        quote!(return Err(::uutils_args::ErrorKind::UnexpectedOption(
+            option,
+            ::uutils_args::internal::short_suggestions(
+                parser,
+                short,
+                &[#(#all_short_flags),*],
+                &[#(#all_long_flags),*],
+                #suggestion_threshold,
+            ),
+        )))
+    };
+
     let token_stream = quote!(
         let option = format!("-{}", short);
         Ok(Some(Argument::Custom(
             match short {
                 #(#match_arms)*
-                _ => return Err(::uutils_args::ErrorKind::UnexpectedOption(short.to_string(), Vec::new())),
+                _ => #unknown,
             }
         )))
     );
     (token_stream, short_flags)
 }
 
-pub fn long_handling(args: &[Argument], help_flags: &Flags) -> TokenStream {
+pub fn long_handling(args: &[Argument], attr: &ArgumentsAttr) -> TokenStream {
+    let ArgumentsAttr {
+        help_flags,
+        case_insensitive_long,
+        suggestion_threshold,
+        passthrough_unknown,
+        ..
+    } = attr;
+
     let mut match_arms = Vec::new();
     let mut options = Vec::new();
 
     options.extend(help_flags.long.iter().map(|f| f.flag.clone()));
 
     for arg in args {
-        let (flags, takes_value, default) = match &arg.arg_type {
-            ArgType::Option {
-                flags,
-                takes_value,
-                ref default,
-                hidden: _,
-            } => (flags, takes_value, default),
-            ArgType::Free { .. } => continue,
-        };
+        let (flags, takes_value, default, deprecated, nargs, greedy, terminator) =
+            match &arg.arg_type {
+                ArgType::Option {
+                    flags,
+                    takes_value,
+                    ref default,
+                    deprecated,
+                    nargs,
+                    hidden_help: _,
+                    hidden_complete: _,
+                    parser: _,
+                    range: _,
+                    error: _,
+                    no_dash_value: _,
+                    greedy,
+                    terminator,
+                } => (
+                    flags,
+                    takes_value,
+                    default,
+                    deprecated,
+                    *nargs,
+                    *greedy,
+                    *terminator,
+                ),
+                ArgType::Free { .. } | ArgType::Subcommand { .. } | ArgType::Operands => continue,
+            };
 
         if flags.long.is_empty() {
             continue;
@@ -200,20 +396,41 @@ pub fn long_handling(args: &[Argument], help_flags: &Flags) -> TokenStream {
         for flag in &flags.long {
             let pat = &flag.flag;
             let expr = match (&flag.value, takes_value) {
-                (Value::No, false) => no_value_expression(&arg.ident),
+                (Value::No, false) => reject_value_expression(no_value_expression(&arg.ident)),
                 (_, false) => {
                     panic!("Option cannot take a value if the variant doesn't have a field")
                 }
-                (Value::No, true) => default_value_expression(&arg.ident, default),
-                (Value::Optional(_), true) => optional_value_expression(&arg.ident, default),
-                (Value::Required(_), true) => required_value_expression(&arg.ident),
+                (Value::No | Value::Optional(_), true) if nargs != 1 => {
+                    panic!("An option with nargs must have flags with a required value")
+                }
+                (Value::No | Value::Optional(_), true) if greedy => {
+                    panic!("A greedy option must have flags with a required value")
+                }
+                (Value::No, true) => {
+                    reject_value_expression(default_value_expression(&arg.ident, default))
+                }
+                (Value::Optional(_), true) => optional_value_expression(arg, default),
+                (Value::Required(_), true) => required_value_expression(arg, nargs),
             };
-            match_arms.push(quote!(#pat => { #expr }));
+            let warning = deprecation_warning(deprecated);
+            let expr = terminator_expression(expr, terminator);
+            match_arms.push(quote!(#pat => { #warning #expr }));
             options.push(flag.flag.clone());
         }
     }
 
+    // Unknown options are passed on with their value, if any.
+    let unknown = quote!({
+        let option = format!("--{long}");
+        return Ok(Some(Argument::Unknown(
+            ::uutils_args::internal::unknown_long_option(parser, option),
+        )));
+    });
+
     if options.is_empty() {
+        if *passthrough_unknown {
+            return unknown;
+        }
         return quote!(
             return Err(::uutils_args::ErrorKind::UnexpectedOption(
                 long.to_string(),
@@ -222,6 +439,14 @@ pub fn long_handling(args: &[Argument], help_flags: &Flags) -> TokenStream {
         );
     }
 
+    let infer_error = if *passthrough_unknown {
+        quote!(
+            Err(::uutils_args::ErrorKind::UnexpectedOption(..)) => { #unknown }
+        )
+    } else {
+        quote!()
+    };
+
     // TODO: Add version check
     let help_check = if !help_flags.long.is_empty() {
         let long_help_flags = help_flags.long.iter().map(|f| &f.flag);
@@ -236,7 +461,15 @@ pub fn long_handling(args: &[Argument], help_flags: &Flags) -> TokenStream {
 
     quote!(
         let long_options: [&str; #num_opts] = [#(#options),*];
-        let long = ::uutils_args::internal::infer_long_option(long, &long_options)?;
+        let long = match ::uutils_args::internal::infer_long_option(
+            long,
+            &long_options,
+            #case_insensitive_long,
+            #suggestion_threshold,
+        ) {
+            #infer_error
+            result => result?,
+        };
 
         #help_check
 
@@ -250,14 +483,32 @@ pub fn long_handling(args: &[Argument], help_flags: &Flags) -> TokenStream {
     )
 }
 
-pub fn free_handling(args: &[Argument]) -> TokenStream {
+pub fn free_handling(args: &[Argument], suggestion_threshold: f64) -> TokenStream {
     let mut if_expressions = Vec::new();
 
-    // Free arguments
+    // Free arguments and plus-prefixed arguments
     for arg @ Argument { arg_type, .. } in args {
         let filters = match arg_type {
             ArgType::Free { filters } => filters,
-            ArgType::Option { .. } => continue,
+            ArgType::Option { flags, .. } => {
+                let ident = &arg.ident;
+                // A plus-prefixed argument is only taken if the value can be
+                // parsed, so that variants can be distinguished by their type.
+                if !flags.plus.is_empty() {
+                    let value =
+                        parse_value_result(arg, quote!("+"), quote!(::std::ffi::OsStr::new(inner)));
+                    if_expressions.push(quote!(
+                        if let Some(inner) = arg.strip_prefix('+') {
+                            if let Ok(value) = #value {
+                                let _ = raw.next();
+                                return Ok(Some(Argument::Custom(Self::#ident(value))));
+                            }
+                        }
+                    ));
+                }
+                continue;
+            }
+            ArgType::Subcommand { .. } | ArgType::Operands => continue,
         };
 
         for filter in filters {
@@ -279,16 +530,18 @@ pub fn free_handling(args: &[Argument]) -> TokenStream {
     for arg @ Argument { arg_type, .. } in args {
         let flags = match arg_type {
             ArgType::Option { flags, .. } => flags,
-            ArgType::Free { .. } => continue,
+            ArgType::Free { .. } | ArgType::Subcommand { .. } | ArgType::Operands => continue,
         };
 
         for (prefix, _) in &flags.dd_style {
             let ident = &arg.ident;
+            let value =
+                parse_value_expression(arg, quote!(""), quote!(::std::ffi::OsStr::new(value)));
 
             dd_args.push(prefix);
             dd_branches.push(quote!(
                 if prefix == #prefix {
-                    let value = ::uutils_args::internal::parse_value_for_option("", ::std::ffi::OsStr::new(value))?;
+                    let value = #value;
                     let _ = raw.next();
                     return Ok(Some(Argument::Custom(Self::#ident(value))));
                 }
@@ -303,7 +556,7 @@ pub fn free_handling(args: &[Argument]) -> TokenStream {
 
                 return Err(::uutils_args::ErrorKind::UnexpectedOption(
                     prefix.to_string(),
-                    ::uutils_args::internal::filter_suggestions(prefix, &[#(#dd_args),*], "")
+                    ::uutils_args::internal::filter_suggestions(prefix, &[#(#dd_args),*], "", #suggestion_threshold)
                 ));
             }
         ));
@@ -318,6 +571,66 @@ pub fn free_handling(args: &[Argument]) -> TokenStream {
     )
 }
 
+pub fn subcommand_handling(args: &[Argument]) -> TokenStream {
+    let mut if_expressions = Vec::new();
+
+    for arg in args {
+        let ArgType::Subcommand { name } = &arg.arg_type else {
+            continue;
+        };
+        let ident = &arg.ident;
+        let ty = arg.field.as_ref().unwrap();
+
+        if_expressions.push(quote!(
+            if value == #name {
+                return Ok(Some(Argument::Subcommand {
+                    name: value,
+                    next_arg: |parser| {
+                        Ok(<#ty as Arguments>::next_arg(parser)?.map(|arg| {
+                            ::uutils_args::internal::wrap_subcommand_argument(arg, Self::#ident)
+                        }))
+                    },
+                    help: <#ty as Arguments>::help,
+                }));
+            }
+        ));
+    }
+
+    quote!(#(#if_expressions)*)
+}
+
+fn deprecation_warning(deprecated: &Option<String>) -> TokenStream {
+    match deprecated {
+        Some(message) => {
+            quote!(::uutils_args::internal::warn_deprecated(parser, &option, #message);)
+        }
+        None => quote!(),
+    }
+}
+
+/// Reject a value attached to a long option that does not take one
+///
+/// lexopt would report this too, but with the abbreviation that was typed
+/// instead of the full name of the option.
+fn reject_value_expression(expr: TokenStream) -> TokenStream {
+    quote!({
+        ::uutils_args::internal::reject_value(parser, &option)?;
+        #expr
+    })
+}
+
+/// End the options after the argument has been parsed, if it is a terminator
+fn terminator_expression(expr: TokenStream, terminator: bool) -> TokenStream {
+    if !terminator {
+        return expr;
+    }
+    quote!({
+        let arg = #expr;
+        ::uutils_args::internal::end_options(parser, &option)?;
+        arg
+    })
+}
+
 fn no_value_expression(ident: &Ident) -> TokenStream {
     quote!(Self::#ident)
 }
@@ -326,13 +639,147 @@ fn default_value_expression(ident: &Ident, default_expr: &TokenStream) -> TokenS
     quote!(Self::#ident(#default_expr))
 }
 
-fn optional_value_expression(ident: &Ident, default_expr: &TokenStream) -> TokenStream {
+/// Parse a value for an option and propagate the error
+fn parse_value_expression(arg: &Argument, option: TokenStream, value: TokenStream) -> TokenStream {
+    let result = parse_value_result(arg, option, value);
+    quote!(#result?)
+}
+
+/// Parse a value for an option with its parser, checking the range if the
+/// option has one, into a `Result`
+fn parse_value_result(arg: &Argument, option: TokenStream, value: TokenStream) -> TokenStream {
+    let result = match &arg.arg_type {
+        ArgType::Option {
+            parser: Some(parser),
+            range,
+            ..
+        } => {
+            let Some(range) = range else {
+                return with_error_template(
+                    arg,
+                    quote!(::uutils_args::internal::parse_value_with(#option, #value, #parser)),
+                );
+            };
+            // The raw value might take an argument from the parser, so it
+            // must only be evaluated once.
+            quote!({
+                let raw_value = #value;
+                ::uutils_args::internal::parse_value_with(#option, raw_value, #parser)
+                    .and_then(|value| ::uutils_args::internal::check_range(#option, raw_value, value, #range))
+            })
+        }
+        ArgType::Option {
+            range: Some(range), ..
+        } => {
+            quote!(::uutils_args::internal::parse_value_in_range(#option, #value, #range))
+        }
+        _ => quote!(::uutils_args::internal::parse_value_for_option(#option, #value)),
+    };
+    with_error_template(arg, result)
+}
+
+/// Apply the `error` template of the option, if any, to the result of
+/// parsing a value
+fn with_error_template(arg: &Argument, result: TokenStream) -> TokenStream {
+    match &arg.arg_type {
+        ArgType::Option {
+            error: Some(template),
+            ..
+        } => quote!(::uutils_args::internal::with_error_template(#result, #template)),
+        _ => result,
+    }
+}
+
+fn optional_value_expression(arg: &Argument, default_expr: &TokenStream) -> TokenStream {
+    let ident = &arg.ident;
+    let value = parse_value_expression(arg, quote!(&option), quote!(&value));
     quote!(match parser.optional_value() {
-        Some(value) => Self::#ident(::uutils_args::internal::parse_value_for_option(&option, &value)?),
+        Some(value) => Self::#ident(#value),
         None => Self::#ident(#default_expr),
     })
 }
 
-fn required_value_expression(ident: &Ident) -> TokenStream {
-    quote!(Self::#ident(::uutils_args::internal::parse_value_for_option(&option, &parser.value()?)?))
+fn required_value_expression(arg: &Argument, nargs: usize) -> TokenStream {
+    let ident = &arg.ident;
+    if let ArgType::Option { greedy: true, .. } = arg.arg_type {
+        let value = parse_value_expression(arg, quote!(&option), quote!(value));
+        return quote!({
+            // The value expression already uses `?`, so it is wrapped in Ok
+            #[allow(clippy::needless_question_mark)]
+            let parse = |value: &::std::ffi::OsStr| Ok(#value);
+            Self::#ident(::uutils_args::internal::greedy_values(parser, &option, parse)?)
+        });
+    }
+    let raw_value = match arg.arg_type {
+        ArgType::Option {
+            no_dash_value: true,
+            ..
+        } => quote!(&::uutils_args::internal::value_without_dash(
+            parser, &option
+        )?),
+        _ => quote!(&parser.value()?),
+    };
+    if nargs == 1 {
+        let value = parse_value_expression(arg, quote!(&option), raw_value);
+        return quote!(Self::#ident(#value));
+    }
+
+    // Multiple values are collected into a tuple or an array, depending on
+    // the type of the field.
+    let values =
+        (0..nargs).map(|_| parse_value_expression(arg, quote!(&option), raw_value.clone()));
+    match &arg.field {
+        Some(syn::Type::Tuple(_)) => quote!(Self::#ident((#(#values),*))),
+        _ => quote!(Self::#ident([#(#values),*])),
+    }
+}
+
+pub fn operands_handling(args: &[Argument]) -> TokenStream {
+    let mut operands = args
+        .iter()
+        .filter(|arg| matches!(arg.arg_type, ArgType::Operands));
+
+    let Some(arg) = operands.next() else {
+        return quote!(Ok(None));
+    };
+    assert!(
+        operands.next().is_none(),
+        "There can only be one operands variant"
+    );
+
+    let ident = &arg.ident;
+    quote!(
+        let values = operands
+            .iter()
+            .map(|operand| ::uutils_args::internal::parse_value_for_option("", operand))
+            .collect::<Result<_, _>>()?;
+        Ok(Some(Self::#ident(values)))
+    )
+}
+
+/// The `REQUIRED_GROUPS` of the arguments, with the first long flag, or
+/// else the first short flag, of each variant to show in errors
+pub fn required_groups(args: &[Argument], groups: &[Vec<String>]) -> TokenStream {
+    let groups = groups.iter().map(|group| {
+        let options = group.iter().map(|variant| {
+            let flags = args
+                .iter()
+                .filter(|arg| arg.ident == variant)
+                .find_map(|arg| match &arg.arg_type {
+                    ArgType::Option { flags, .. } => Some(flags),
+                    _ => None,
+                })
+                .unwrap_or_else(|| {
+                    panic!("required_group contains '{variant}', which is not an option")
+                });
+            let flag = match (flags.long.first(), flags.short.first()) {
+                (Some(long), _) => format!("--{}", long.flag),
+                (None, Some(short)) => format!("-{}", short.flag),
+                (None, None) => panic!("required_group contains '{variant}', which has no flags"),
+            };
+            quote!((#variant, #flag))
+        });
+        quote!(&[#(#options),*])
+    });
+    quote!(&[#(#groups),*])
 }
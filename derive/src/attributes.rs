@@ -2,29 +2,69 @@
 // file that was distributed with this source code.
 
 use syn::{
-    meta::ParseNestedMeta, parse::ParseStream, Attribute, Expr, Ident, LitInt, LitStr, Token,
+    meta::ParseNestedMeta, parse::ParseStream, Attribute, Expr, Ident, LitFloat, LitInt, LitStr,
+    Token,
 };
 
 use crate::flags::Flags;
 
 pub struct ArgumentsAttr {
     pub help_flags: Flags,
+    pub help_description: String,
     pub version_flags: Flags,
+    pub version_description: String,
+    pub version_extra: Option<String>,
+    pub hide_version: bool,
     pub file: Option<String>,
+    pub file_base: FileBase,
+    /// The exit code for usage errors
     pub exit_code: i32,
+    /// The exit code for other errors
+    pub error_exit_code: i32,
     pub parse_echo_style: bool,
     pub options_first: bool,
+    pub case_insensitive_long: bool,
+    pub suggestion_threshold: f64,
+    pub help_short_circuits: bool,
+    pub passthrough_unknown: bool,
+    pub response_files: bool,
+    /// The variant names of the groups of which exactly one must be given
+    pub required_groups: Vec<Vec<String>>,
+    /// The operands in their usage form, like `[FILE]...`
+    pub usage_operands: Vec<String>,
+}
+
+/// The directory that the help file is resolved relative to
+#[derive(Clone, Copy)]
+pub enum FileBase {
+    /// The directory containing the `Cargo.toml` of the crate
+    Manifest,
+    /// The directory containing the source file with the derive
+    Source,
 }
 
 impl Default for ArgumentsAttr {
     fn default() -> Self {
         Self {
             help_flags: Flags::new(["--help"]),
+            help_description: "Display this help message".into(),
             version_flags: Flags::new(["--version"]),
+            version_description: "Display version information".into(),
+            version_extra: None,
+            hide_version: false,
             file: None,
+            file_base: FileBase::Manifest,
             exit_code: 1,
+            error_exit_code: 1,
             parse_echo_style: false,
             options_first: false,
+            case_insensitive_long: false,
+            suggestion_threshold: 0.7,
+            help_short_circuits: false,
+            passthrough_unknown: false,
+            response_files: false,
+            required_groups: Vec::new(),
+            usage_operands: Vec::new(),
         }
     }
 }
@@ -46,13 +86,47 @@ impl ArgumentsAttr {
                     let strings = assert_expr_is_array_of_litstr(expr, "version_flags")?;
                     args.version_flags = Flags::new(strings);
                 }
+                "help_description" => {
+                    args.help_description = meta.value()?.parse::<LitStr>()?.value();
+                }
+                "version_description" => {
+                    args.version_description = meta.value()?.parse::<LitStr>()?.value();
+                }
+                "version_extra" => {
+                    args.version_extra = Some(meta.value()?.parse::<LitStr>()?.value());
+                }
+                "hide_version" => {
+                    args.hide_version = true;
+                }
                 "file" => {
                     let s = meta.value()?.parse::<LitStr>()?.value();
                     args.file = Some(s);
                 }
+                "file_base" => {
+                    let s = meta.value()?.parse::<LitStr>()?;
+                    args.file_base = match s.value().as_str() {
+                        "manifest" => FileBase::Manifest,
+                        "source" => FileBase::Source,
+                        _ => {
+                            return Err(syn::Error::new_spanned(
+                                s,
+                                "file_base must be \"manifest\" or \"source\"",
+                            ))
+                        }
+                    };
+                }
                 "exit_code" => {
                     let c = meta.value()?.parse::<LitInt>()?.base10_parse()?;
                     args.exit_code = c;
+                    args.error_exit_code = c;
+                }
+                "usage_exit_code" => {
+                    let c = meta.value()?.parse::<LitInt>()?.base10_parse()?;
+                    args.exit_code = c;
+                }
+                "error_exit_code" => {
+                    let c = meta.value()?.parse::<LitInt>()?.base10_parse()?;
+                    args.error_exit_code = c;
                 }
                 "parse_echo_style" => {
                     args.parse_echo_style = true;
@@ -60,6 +134,31 @@ impl ArgumentsAttr {
                 "options_first" => {
                     args.options_first = true;
                 }
+                "case_insensitive_long" => {
+                    args.case_insensitive_long = true;
+                }
+                "help_short_circuits" => {
+                    args.help_short_circuits = true;
+                }
+                "passthrough_unknown" => {
+                    args.passthrough_unknown = true;
+                }
+                "response_files" => {
+                    args.response_files = true;
+                }
+                "usage_operands" => {
+                    let expr: Expr = meta.value()?.parse()?;
+                    args.usage_operands = assert_expr_is_array_of_litstr(expr, "usage_operands")?;
+                }
+                "required_group" => {
+                    let expr: Expr = meta.value()?.parse()?;
+                    let group = assert_expr_is_array_of_litstr(expr, "required_group")?;
+                    args.required_groups.push(group);
+                }
+                "suggestion_threshold" => {
+                    let t = meta.value()?.parse::<LitFloat>()?.base10_parse()?;
+                    args.suggestion_threshold = t;
+                }
                 _ => return Err(meta.error("unrecognized argument for arguments attribute")),
             };
             Ok(())
@@ -69,9 +168,12 @@ impl ArgumentsAttr {
     }
 }
 
+#[allow(clippy::large_enum_variant)]
 pub enum ArgAttr {
     Option(OptionAttr),
     Free(FreeAttr),
+    Subcommand(SubcommandAttr),
+    Operands,
 }
 
 impl ArgAttr {
@@ -82,13 +184,22 @@ impl ArgAttr {
             // Based on the first value, we determine the type of argument.
             if let Ok(litstr) = s.parse::<LitStr>() {
                 let v = litstr.value();
-                if v.starts_with('-') || v.contains('=') {
+                if v.starts_with('-') || v.starts_with('+') || v.contains('=') {
                     OptionAttr::from_args(v, s).map(Self::Option)
                 } else {
                     panic!("Could not determine type of argument");
                 }
             } else if let Ok(v) = s.parse::<syn::Ident>() {
-                FreeAttr::from_args(v, s).map(Self::Free)
+                if v == "subcommand" {
+                    SubcommandAttr::from_args(s).map(Self::Subcommand)
+                } else if v == "operands" {
+                    if !s.is_empty() {
+                        return Err(s.error("unexpected argument for operands"));
+                    }
+                    Ok(Self::Operands)
+                } else {
+                    FreeAttr::from_args(v, s).map(Self::Free)
+                }
             } else {
                 // TODO: Improve error message
                 panic!("Could not determine type of argument");
@@ -102,8 +213,17 @@ pub struct OptionAttr {
     pub flags: Flags,
     pub parser: Option<Expr>,
     pub value: Option<Expr>,
-    pub hidden: bool,
+    pub hidden_help: bool,
+    pub hidden_complete: bool,
     pub help: Option<String>,
+    pub deprecated: Option<String>,
+    pub nargs: Option<usize>,
+    pub range: Option<Expr>,
+    pub error: Option<String>,
+    pub help_section: Option<String>,
+    pub no_dash_value: bool,
+    pub greedy: bool,
+    pub terminator: bool,
 }
 
 impl OptionAttr {
@@ -130,13 +250,73 @@ impl OptionAttr {
                     option_attr.value = Some(d);
                 }
                 "hidden" => {
-                    option_attr.hidden = true;
+                    option_attr.hidden_help = true;
+                    option_attr.hidden_complete = true;
+                }
+                "hidden_help" => {
+                    option_attr.hidden_help = true;
+                }
+                "hidden_complete" => {
+                    option_attr.hidden_complete = true;
+                }
+                "alias" => {
+                    s.parse::<Token![=]>()?;
+                    let a = s.parse::<LitStr>()?;
+                    option_attr.flags.add_alias(&a.value());
+                }
+                "no_dash_value" => {
+                    option_attr.no_dash_value = true;
+                }
+                "greedy" => {
+                    option_attr.greedy = true;
+                }
+                "terminator" => {
+                    option_attr.terminator = true;
                 }
                 "help" => {
                     s.parse::<Token![=]>()?;
                     let h = s.parse::<LitStr>()?;
                     option_attr.help = Some(h.value());
                 }
+                "help_section" => {
+                    s.parse::<Token![=]>()?;
+                    let h = s.parse::<LitStr>()?;
+                    option_attr.help_section = Some(h.value());
+                }
+                "deprecated" => {
+                    s.parse::<Token![=]>()?;
+                    let d = s.parse::<LitStr>()?;
+                    option_attr.deprecated = Some(d.value());
+                }
+                "error" => {
+                    s.parse::<Token![=]>()?;
+                    let e = s.parse::<LitStr>()?;
+                    check_error_template(&e)?;
+                    option_attr.error = Some(e.value());
+                }
+                "range" => {
+                    s.parse::<Token![=]>()?;
+                    let r = s.parse::<Expr>()?;
+                    option_attr.range = Some(r);
+                }
+                "default" => {
+                    return Err(syn::Error::new_spanned(
+                        ident,
+                        "`default` is not supported, use `value = ...` instead",
+                    ))
+                }
+                "nargs" => {
+                    s.parse::<Token![=]>()?;
+                    let lit = s.parse::<LitInt>()?;
+                    let n = lit.base10_parse()?;
+                    if n == 0 {
+                        return Err(syn::Error::new_spanned(
+                            lit,
+                            "`nargs` must be at least 1, use a flag without a value instead",
+                        ));
+                    }
+                    option_attr.nargs = Some(n);
+                }
                 _ => {
                     return Err(syn::Error::new_spanned(
                         ident,
@@ -171,10 +351,81 @@ impl FreeAttr {
     }
 }
 
+#[derive(Default)]
+pub struct SubcommandAttr {
+    pub name: Option<String>,
+}
+
+impl SubcommandAttr {
+    pub fn from_args(s: ParseStream) -> syn::Result<Self> {
+        let mut subcommand_attr = SubcommandAttr::default();
+
+        if s.parse::<Token![=]>().is_ok() {
+            subcommand_attr.name = Some(s.parse::<LitStr>()?.value());
+        }
+
+        if !s.is_empty() {
+            return Err(s.error("unexpected argument for subcommand"));
+        }
+
+        Ok(subcommand_attr)
+    }
+}
+
+/// How a `Value` enum resolves a prefix that matches multiple keys
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+pub enum Ambiguity {
+    /// Return an error listing the candidates
+    #[default]
+    Error,
+    /// Take the key of the first declared variant that matches
+    First,
+    /// Do not accept prefixes at all, only exact matches
+    ExactOnly,
+}
+
+/// The `#[value(...)]` attribute on a `Value` enum itself
+#[derive(Default)]
+pub struct ValueEnumAttr {
+    pub ambiguity: Ambiguity,
+}
+
+impl ValueEnumAttr {
+    pub fn parse(attr: &Attribute) -> syn::Result<Self> {
+        let mut value_attr = Self::default();
+
+        attr.parse_nested_meta(|meta| {
+            let ident = get_ident(&meta)?;
+            match ident.as_str() {
+                "ambiguity" => {
+                    let s = meta.value()?.parse::<LitStr>()?;
+                    value_attr.ambiguity = match s.value().as_str() {
+                        "error" => Ambiguity::Error,
+                        "first" => Ambiguity::First,
+                        "exact_only" => Ambiguity::ExactOnly,
+                        _ => {
+                            return Err(syn::Error::new_spanned(
+                                s,
+                                "ambiguity must be \"error\", \"first\" or \"exact_only\"",
+                            ))
+                        }
+                    };
+                }
+                _ => return Err(meta.error("unrecognized argument for value attribute")),
+            };
+            Ok(())
+        })?;
+
+        Ok(value_attr)
+    }
+}
+
 #[derive(Default)]
 pub struct ValueAttr {
     pub keys: Vec<String>,
     pub value: Option<Expr>,
+    /// Whether this variant captures all values that match no key
+    pub other: bool,
 }
 
 impl ValueAttr {
@@ -197,6 +448,9 @@ impl ValueAttr {
                         let p = s.parse::<Expr>()?;
                         value_attr.value = Some(p);
                     }
+                    "other" => {
+                        value_attr.other = true;
+                    }
                     _ => return Err(s.error("unrecognized keyword in value attribute")),
                 }
             }
@@ -266,3 +520,35 @@ fn assert_expr_is_array_of_litstr(expr: Expr, flag: &str) -> syn::Result<Vec<Str
     }
     Ok(strings)
 }
+
+/// Check that the `error` template only has known placeholders
+///
+/// Like in `format!`, `{{` and `}}` are escaped braces.
+fn check_error_template(template: &LitStr) -> syn::Result<()> {
+    const PLACEHOLDERS: [&str; 3] = ["value", "option", "error"];
+    let value = template.value();
+    let mut rest = value.as_str();
+    while let Some(start) = rest.find(['{', '}']) {
+        let (brace, after) = rest[start..].split_at(1);
+        if let Some(after) = after.strip_prefix(brace) {
+            rest = after;
+            continue;
+        }
+        let Some((name, after)) = after.split_once('}').filter(|_| brace == "{") else {
+            return Err(syn::Error::new_spanned(
+                template,
+                format!("unmatched `{brace}` in error template, use `{brace}{brace}` for a literal brace"),
+            ));
+        };
+        if !PLACEHOLDERS.contains(&name) {
+            return Err(syn::Error::new_spanned(
+                template,
+                format!(
+                    "unknown placeholder `{{{name}}}` in error template, expected one of `{{value}}`, `{{option}}` or `{{error}}`"
+                ),
+            ));
+        }
+        rest = after;
+    }
+    Ok(())
+}
@@ -3,91 +3,158 @@
 
 use crate::{
     argument::{ArgType, Argument},
+    attributes::ArgumentsAttr,
     flags::{Flag, Flags, Value},
 };
 use proc_macro2::TokenStream;
 use quote::quote;
 
-pub fn complete(args: &[Argument], file: &Option<String>) -> TokenStream {
-    let mut arg_specs = Vec::new();
+/// The arguments that are described in the table of flags
+///
+/// Only options with at least one short or long flag are included, in the
+/// order in which they are defined.
+fn flag_args(args: &[Argument]) -> impl Iterator<Item = (&Argument, &Flags)> {
+    args.iter().filter_map(|arg| match &arg.arg_type {
+        ArgType::Option { flags, .. } if !flags.short.is_empty() || !flags.long.is_empty() => {
+            Some((arg, flags))
+        }
+        _ => None,
+    })
+}
+
+/// The table of all flags, as a `&'static [FlagInfo]` expression
+pub fn flags_table(args: &[Argument]) -> TokenStream {
+    let mut infos = Vec::new();
+
+    for (arg, flags) in flag_args(args) {
+        let ArgType::Option {
+            hidden_help,
+            hidden_complete,
+            ..
+        } = arg.arg_type
+        else {
+            unreachable!("only options are in the table")
+        };
+        let help = &arg.help;
+
+        let flag = |flag: String, value: &Value, alias: bool| {
+            let value = match value {
+                Value::No => quote!(::uutils_args::flags::FlagValue::No),
+                Value::Optional(name) => quote!(::uutils_args::flags::FlagValue::Optional(#name)),
+                Value::Required(name) => quote!(::uutils_args::flags::FlagValue::Required(#name)),
+            };
+            quote!(::uutils_args::flags::Flag {
+                flag: #flag,
+                value: #value,
+                alias: #alias,
+            })
+        };
+
+        let short = flags.short.iter().map(
+            |Flag {
+                 flag: f,
+                 value,
+                 alias,
+             }| flag(f.to_string(), value, *alias),
+        );
+        let long = flags.long.iter().map(
+            |Flag {
+                 flag: f,
+                 value,
+                 alias,
+             }| flag(f.clone(), value, *alias),
+        );
+
+        infos.push(quote!(
+            ::uutils_args::flags::FlagInfo {
+                short: &[#(#short),*],
+                long: &[#(#long),*],
+                help: #help,
+                hidden_help: #hidden_help,
+                hidden_complete: #hidden_complete,
+            }
+        ));
+    }
+
+    quote!(&[#(#infos),*])
+}
 
-    let (summary, _usage, after_options) = if let Some(file) = file {
-        crate::help::read_help_file(file)
+pub fn complete(args: &[Argument], arguments_attr: &ArgumentsAttr) -> TokenStream {
+    let (summary, after_options) = if let Some(file) = &arguments_attr.file {
+        let (summary, _usage, after_options) =
+            crate::help::read_help_file(file, arguments_attr.file_base);
+        (summary, after_options)
     } else {
-        ("".into(), "{} [OPTIONS] [ARGUMENTS]".into(), "".into())
+        ("".into(), "".into())
     };
+    let operands = &arguments_attr.usage_operands;
 
-    for Argument {
-        help,
-        field,
-        arg_type,
-        ..
-    } in args
-    {
+    // The hints are in the same order as the table of flags, so they can be
+    // zipped together.
+    let mut hints = Vec::new();
+    for (arg, flags) in flag_args(args) {
         let ArgType::Option {
-            flags,
-            hidden: false,
+            nargs,
+            parser,
+            greedy,
             ..
-        } = arg_type
+        } = &arg.arg_type
         else {
-            continue;
+            unreachable!("only options are in the table")
         };
-
-        let Flags { short, long, .. } = flags;
-        if short.is_empty() && long.is_empty() {
-            continue;
-        }
+        let Flags {
+            short,
+            long,
+            dd_style,
+            ..
+        } = flags;
 
         // If none of the flags take an argument, we won't need ValueHint
         // based on that type. So we should not attempt to call `value_hint`
         // on it.
-        let any_flag_takes_argument =
-            short.iter().any(|f| f.value != Value::No) && long.iter().any(|f| f.value != Value::No);
-
-        let short: Vec<_> = short
-            .iter()
-            .map(|Flag { flag, value }| {
-                let flag = flag.to_string();
-                let value = match value {
-                    Value::No => quote!(::uutils_args_complete::Value::No),
-                    Value::Optional(name) => quote!(::uutils_args_complete::Value::Optional(#name)),
-                    Value::Required(name) => quote!(::uutils_args_complete::Value::Required(#name)),
-                };
-                quote!(::uutils_args_complete::Flag {
-                    flag: #flag,
-                    value: #value
-                })
-            })
-            .collect();
+        let any_flag_takes_argument = short.iter().any(|f| f.value != Value::No)
+            || long.iter().any(|f| f.value != Value::No)
+            || !dd_style.is_empty();
 
-        let long: Vec<_> = long
-            .iter()
-            .map(|Flag { flag, value }| {
-                let value = match value {
-                    Value::No => quote!(::uutils_args_complete::Value::No),
-                    Value::Optional(name) => quote!(::uutils_args_complete::Value::Optional(#name)),
-                    Value::Required(name) => quote!(::uutils_args_complete::Value::Required(#name)),
-                };
-                quote!(::uutils_args_complete::Flag {
-                    flag: #flag,
-                    value: #value
-                })
-            })
-            .collect();
-
-        let hint = match (field, any_flag_takes_argument) {
+        let hint = match (&arg.field, any_flag_takes_argument) {
+            // A custom parser means that the type might not implement
+            // Value, so there is no hint for it.
+            (Some(_), true) if parser.is_some() => {
+                quote!(Some(::uutils_args_complete::ValueHint::Unknown))
+            }
+            // Options with multiple values hold an array or a tuple, so the
+            // hint can only be determined for the elements of an array.
+            (Some(syn::Type::Array(array)), true) if *nargs != 1 => {
+                let ty = &array.elem;
+                quote!(Some(<#ty>::value_hint()))
+            }
+            (Some(_), true) if *nargs != 1 => {
+                quote!(Some(::uutils_args_complete::ValueHint::Unknown))
+            }
+            // Greedy options collect their values, usually into a Vec
+            (Some(ty), true) if *greedy => {
+                quote!(Some(::uutils_args::internal::element_value_hint::<#ty>()))
+            }
             (Some(ty), true) => quote!(Some(<#ty>::value_hint())),
             _ => quote!(None),
         };
+        hints.push(hint);
+    }
 
-        arg_specs.push(quote!(
-            ::uutils_args_complete::Arg {
-                short: vec![#(#short),*],
-                long: vec![#(#long),*],
-                help: #help,
-                value: #hint,
-            }
-        ))
+    // The help and version flags are not variants, but should be completed
+    // as well.
+    let mut extra_args = Vec::new();
+    if !arguments_attr.help_flags.is_empty() {
+        extra_args.push(complete_flags_arg(
+            &arguments_attr.help_flags,
+            &arguments_attr.help_description,
+        ));
+    }
+    if !arguments_attr.version_flags.is_empty() && !arguments_attr.hide_version {
+        extra_args.push(complete_flags_arg(
+            &arguments_attr.version_flags,
+            &arguments_attr.version_description,
+        ));
     }
 
     quote!(::uutils_args_complete::Command {
@@ -95,8 +162,32 @@ pub fn complete(args: &[Argument], file: &Option<String>) -> TokenStream {
         summary: #summary,
         after_options: #after_options,
         version: env!("CARGO_PKG_VERSION"),
-        args: vec![#(#arg_specs),*],
+        args: ::uutils_args::internal::complete_args(Self::flags(), vec![#(#hints),*])
+            .into_iter()
+            .chain([#(#extra_args),*])
+            .collect(),
+        operands: vec![#(#operands),*],
         license: env!("CARGO_PKG_LICENSE"),
         authors: env!("CARGO_PKG_AUTHORS"),
     })
 }
+
+/// An argument for completions for flags that do not take a value
+fn complete_flags_arg(flags: &Flags, help: &str) -> TokenStream {
+    let short = flags.short.iter().map(|f| f.flag.to_string());
+    let long = flags.long.iter().map(|f| &f.flag);
+    quote!(::uutils_args_complete::Arg {
+        short: vec![#(::uutils_args_complete::Flag {
+            flag: #short,
+            value: ::uutils_args_complete::Value::No,
+        }),*],
+        long: vec![#(::uutils_args_complete::Flag {
+            flag: #long,
+            value: ::uutils_args_complete::Value::No,
+        }),*],
+        help: #help,
+        value: None,
+        hidden_help: false,
+        hidden_complete: false,
+    })
+}
@@ -9,6 +9,8 @@ pub struct Flags {
     pub short: Vec<Flag<char>>,
     pub long: Vec<Flag<String>>,
     pub dd_style: Vec<(String, String)>,
+    /// The value names of `+VALUE` arguments
+    pub plus: Vec<String>,
 }
 
 #[derive(Clone, PartialEq, Eq)]
@@ -22,6 +24,8 @@ pub enum Value {
 pub struct Flag<T> {
     pub flag: T,
     pub value: Value,
+    /// Aliases are parsed, but not shown in the help and completions
+    pub alias: bool,
 }
 
 impl Flags {
@@ -35,10 +39,11 @@ impl Flags {
 
     pub fn add(&mut self, flag: &str) {
         if let Some(s) = flag.strip_prefix("--") {
-            // There are three possible patterns:
+            // There are four possible patterns:
             //   --flag
             //   --flag=value
             //   --flag[=value]
+            //   --flag value1 value2 (for options with multiple values)
 
             // First we trim up to the = or [
             let mut chars = s.chars();
@@ -46,7 +51,7 @@ impl Flags {
             let f: String = (&mut chars)
                 .take_while(|&c: &char| {
                     sep = c;
-                    c.is_alphanumeric() || c == '-'
+                    is_long_flag_char(c)
                 })
                 .collect();
             let val: String = chars.collect();
@@ -57,6 +62,11 @@ impl Flags {
             } else if sep == '=' {
                 assert!(val.chars().all(|c: char| c.is_alphanumeric() || c == '-'));
                 Value::Required(val)
+            } else if sep == ' ' {
+                assert!(val
+                    .chars()
+                    .all(|c: char| c.is_alphanumeric() || c == '-' || c == ' '));
+                Value::Required(val)
             } else if sep == '[' {
                 let optional = val
                     .strip_prefix('=')
@@ -70,7 +80,11 @@ impl Flags {
                 panic!("Invalid long flag '{flag}'");
             };
 
-            self.long.push(Flag { flag: f, value });
+            self.long.push(Flag {
+                flag: f,
+                value,
+                alias: false,
+            });
         } else if let Some(s) = flag.strip_prefix('-') {
             assert!(!s.is_empty());
 
@@ -95,12 +109,21 @@ impl Flags {
             } else if let Some(required) = val.strip_prefix(' ') {
                 assert!(required
                     .chars()
-                    .all(|c: char| c.is_alphanumeric() || c == '-'));
+                    .all(|c: char| c.is_alphanumeric() || c == '-' || c == ' '));
                 Value::Required(required.into())
             } else {
                 panic!("Invalid short flag '{flag}'")
             };
-            self.short.push(Flag { flag: f, value });
+            self.short.push(Flag {
+                flag: f,
+                value,
+                alias: false,
+            });
+        } else if let Some(v) = flag.strip_prefix('+') {
+            // It's a plus-prefixed argument: +value
+            assert!(!v.is_empty());
+
+            self.plus.push(v.into());
         } else if let Some((s, v)) = flag.split_once('=') {
             // It's a dd-style argument: arg=value
             assert!(!s.is_empty());
@@ -110,8 +133,27 @@ impl Flags {
         }
     }
 
+    /// Add a short or long flag that is accepted, but not shown
+    pub fn add_alias(&mut self, flag: &str) {
+        let (short, long) = (self.short.len(), self.long.len());
+        self.add(flag);
+        assert!(
+            self.short.len() + self.long.len() == short + long + 1,
+            "Only short and long flags can be aliases, found '{flag}'"
+        );
+        for f in &mut self.short[short..] {
+            f.alias = true;
+        }
+        for f in &mut self.long[long..] {
+            f.alias = true;
+        }
+    }
+
     pub fn is_empty(&self) -> bool {
-        self.short.is_empty() && self.long.is_empty() && self.dd_style.is_empty()
+        self.short.is_empty()
+            && self.long.is_empty()
+            && self.dd_style.is_empty()
+            && self.plus.is_empty()
     }
 
     pub fn pat(&self) -> TokenStream {
@@ -127,10 +169,18 @@ impl Flags {
         }
     }
 
+    /// All flags as they would be written on the command line
+    pub fn strings(&self) -> Vec<String> {
+        let short = self.short.iter().map(|f| format!("-{}", f.flag));
+        let long = self.long.iter().map(|f| format!("--{}", f.flag));
+        short.chain(long).collect()
+    }
+
     pub fn format(&self) -> String {
         let short = self
             .short
             .iter()
+            .filter(|f| !f.alias)
             .map(|f| {
                 let s = &f.flag;
                 match &f.value {
@@ -145,14 +195,17 @@ impl Flags {
         let long = self
             .long
             .iter()
+            .filter(|f| !f.alias)
             .map(|f| {
                 let l = &f.flag;
                 match &f.value {
                     Value::No => format!("--{l}"),
                     Value::Optional(v) => format!("--{l}[={v}]"),
+                    Value::Required(v) if v.contains(' ') => format!("--{l} {v}"),
                     Value::Required(v) => format!("--{l}={v}"),
                 }
             })
+            .chain(self.plus.iter().map(|v| format!("+{v}")))
             .collect::<Vec<_>>()
             .join(", ");
 
@@ -165,3 +218,11 @@ impl Flags {
         }
     }
 }
+
+/// Whether `c` can be part of the name of a long flag
+///
+/// Besides alphanumeric characters and `-`, this allows some punctuation
+/// for options like `--log.level`, `--snake_case` and `--enable+feature`.
+fn is_long_flag_char(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '-' | '.' | '_' | '+')
+}
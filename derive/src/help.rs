@@ -1,15 +1,13 @@
 // For the full copyright and license information, please view the LICENSE
 // file that was distributed with this source code.
 
-use std::{
-    io::Read,
-    path::{Path, PathBuf},
-};
+use std::path::{Path, PathBuf};
 
 use crate::{
     argument::{ArgType, Argument},
+    attributes::{ArgumentsAttr, FileBase},
     flags::Flags,
-    help_parser::{parse_about, parse_section, parse_usage},
+    help_parser::{parse_about, parse_section, parse_usage, render_links, render_section},
 };
 use proc_macro2::TokenStream;
 use quote::quote;
@@ -28,13 +26,20 @@ pub fn help_handling(help_flags: &Flags) -> TokenStream {
     )
 }
 
-pub fn help_string(
-    args: &[Argument],
-    help_flags: &Flags,
-    version_flags: &Flags,
-    file: &Option<String>,
-) -> TokenStream {
+pub fn help_string(args: &[Argument], arguments_attr: &ArgumentsAttr) -> TokenStream {
+    let ArgumentsAttr {
+        help_flags,
+        help_description,
+        version_flags,
+        version_description,
+        hide_version,
+        file,
+        file_base,
+        ..
+    } = arguments_attr;
+
     let mut options = Vec::new();
+    let mut subcommands = Vec::new();
 
     let width: usize = 16;
     let indent: usize = 2;
@@ -43,34 +48,57 @@ pub fn help_string(
         match arg_type {
             ArgType::Option {
                 flags,
-                hidden: false,
+                hidden_help: false,
                 ..
             } => {
                 let flags = flags.format();
                 options.push(quote!((#flags, #help)));
             }
             // Hidden arguments should not show up in --help
-            ArgType::Option { hidden: true, .. } => {}
+            ArgType::Option {
+                hidden_help: true, ..
+            } => {}
             // TODO: Free arguments should show up in help
             ArgType::Free { .. } => {}
+            ArgType::Subcommand { name } => {
+                subcommands.push(quote!((#name, #help)));
+            }
+            // Operands are not options
+            ArgType::Operands => {}
         }
     }
 
+    let dependency = file
+        .as_ref()
+        .map(|file| help_file_dependency(file, *file_base));
+
     // FIXME: We need to get an option per item and provide proper defaults
     let (summary, usage, after_options) = if let Some(file) = file {
-        read_help_file(file)
+        // Links cannot be shown in a terminal, so we show the URL instead
+        let (summary, usage, after_options) = read_help_file(file, *file_base);
+        (render_links(&summary), usage, render_links(&after_options))
+    } else {
+        ("".into(), String::new(), "".into())
+    };
+    let sections = match file {
+        Some(file) => read_extra_sections(file, *file_base),
+        None => String::new(),
+    };
+    // Without a help file or a usage in it, the usage lists the operands.
+    let usage = if usage.is_empty() {
+        default_usage(&arguments_attr.usage_operands)
     } else {
-        ("".into(), "{} [OPTIONS] [ARGUMENTS]".into(), "".into())
+        usage
     };
 
     if !help_flags.is_empty() {
         let flags = help_flags.format();
-        options.push(quote!((#flags, "Display this help message")));
+        options.push(quote!((#flags, #help_description)));
     }
 
-    if !version_flags.is_empty() {
+    if !version_flags.is_empty() && !hide_version {
         let flags = version_flags.format();
-        options.push(quote!((#flags, "Display version information")));
+        options.push(quote!((#flags, #version_description)));
     }
 
     let options = if !options.is_empty() {
@@ -79,13 +107,17 @@ pub fn help_string(
         quote!()
     };
 
+    let subcommands = if !subcommands.is_empty() {
+        quote!(::uutils_args::internal::print_subcommands(&mut w, #indent, #width, [#(#subcommands),*]);)
+    } else {
+        quote!()
+    };
+
     quote!(
+        #dependency
         let mut w = String::new();
         use ::std::fmt::Write;
-        writeln!(w, "{} {}",
-            option_env!("CARGO_BIN_NAME").unwrap_or(env!("CARGO_PKG_NAME")),
-            env!("CARGO_PKG_VERSION"),
-        ).unwrap();
+        writeln!(w, "{} {}", name, env!("CARGO_PKG_VERSION")).unwrap();
 
         writeln!(w, "{}", #summary).unwrap();
 
@@ -93,20 +125,31 @@ pub fn help_string(
 
         #options
 
+        #subcommands
+
+        w.push_str(#sections);
+
         writeln!(w, "{}", #after_options).unwrap();
         w
     )
 }
 
-pub fn read_help_file(file: &str) -> (String, String, String) {
-    let path = Path::new(file);
-    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
-    let mut location = PathBuf::from(manifest_dir);
-    location.push(path);
-    let mut contents = String::new();
-    let mut f = std::fs::File::open(location).unwrap();
-    f.read_to_string(&mut contents).unwrap();
+/// The usage when the help file does not have one, as a format string for
+/// the binary name
+///
+/// This lists the operands given with `#[arguments(usage_operands = [...])]`, e.g.
+/// `{} [OPTIONS] SOURCE... DEST`.
+pub fn default_usage(operands: &[String]) -> String {
+    if operands.is_empty() {
+        return "{} [OPTIONS] [ARGUMENTS]".into();
+    }
+    let operands = operands.join(" ").replace('{', "{{").replace('}', "}}");
+    format!("{{}} [OPTIONS] {operands}")
+}
 
+/// Read the help file and split it into the summary, usage and after help
+pub fn read_help_file(file: &str, base: FileBase) -> (String, String, String) {
+    let contents = read_help_contents(file, base);
     (
         parse_about(&contents),
         parse_usage(&contents),
@@ -114,6 +157,75 @@ pub fn read_help_file(file: &str) -> (String, String, String) {
     )
 }
 
+/// The sections of the help file that are shown between the options and the
+/// after help, in this order, with their headings in the help
+const EXTRA_SECTIONS: &[(&str, &str)] = &[("examples", "Examples"), ("environment", "Environment")];
+
+/// Read and render the sections of [`EXTRA_SECTIONS`] that the help file has
+fn read_extra_sections(file: &str, base: FileBase) -> String {
+    let contents = read_help_contents(file, base);
+    EXTRA_SECTIONS
+        .iter()
+        .filter_map(|(section, heading)| {
+            let content = parse_section(section, &contents)?;
+            Some(format!("\n{}", render_section(heading, &content)))
+        })
+        .collect()
+}
+
+/// Read the help text of an argument from a section of the help file
+pub fn read_help_section(file: &str, base: FileBase, section: &str) -> String {
+    let contents = read_help_contents(file, base);
+    match parse_section(section, &contents) {
+        Some(help) => render_links(&help),
+        None => panic!("help file '{file}' does not have a section '## {section}'"),
+    }
+}
+
+fn read_help_contents(file: &str, base: FileBase) -> String {
+    std::fs::read_to_string(help_file_path(file, base)).unwrap()
+}
+
+/// Embed the help file with `include_str!`, so that the compiler tracks it
+/// and the crate is rebuilt when the file changes.
+pub fn help_file_dependency(file: &str, base: FileBase) -> TokenStream {
+    let path = help_file_path(file, base);
+    let path = path
+        .to_str()
+        .expect("path to help file must be valid UTF-8");
+    quote!(
+        const _: &str = include_str!(#path);
+    )
+}
+
+/// Resolve the absolute path to the help file, panicking with the attempted
+/// location if the file cannot be found.
+fn help_file_path(file: &str, base: FileBase) -> PathBuf {
+    let (base_name, base_dir) = match base {
+        FileBase::Manifest => (
+            "the manifest directory",
+            PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").unwrap()),
+        ),
+        FileBase::Source => {
+            let source = proc_macro::Span::call_site()
+                .local_file()
+                .expect("the source file of the derive is not known, so the help file cannot be resolved relative to it");
+            let dir = source.parent().map(Path::to_path_buf).unwrap_or_default();
+            ("the directory of the source file", dir)
+        }
+    };
+    let location = base_dir.join(file);
+    let absolute = std::path::absolute(&location).unwrap_or(location);
+    if let Err(err) = std::fs::metadata(&absolute) {
+        panic!(
+            "could not open help file '{file}' at '{}', resolved relative to {base_name} '{}': {err}",
+            absolute.display(),
+            base_dir.display(),
+        )
+    }
+    absolute
+}
+
 pub fn version_handling(version_flags: &Flags) -> TokenStream {
     if version_flags.is_empty() {
         return quote!();
@@ -23,8 +23,10 @@
 
 const MARKDOWN_CODE_FENCES: &str = "```";
 
-/// Parses the text between the first markdown code block and the next header, if any,
-/// into an about string.
+/// Parses the text between the first markdown code block and the next first
+/// or second level header, if any, into an about string.
+///
+/// Deeper headers (`###` to `######`) are part of the about string.
 pub fn parse_about(content: &str) -> String {
     content
         .lines()
@@ -32,7 +34,7 @@ pub fn parse_about(content: &str) -> String {
         .skip(1)
         .skip_while(|l| !l.starts_with(MARKDOWN_CODE_FENCES))
         .skip(1)
-        .take_while(|l| !l.starts_with('#'))
+        .take_while(|l| !matches!(l.find(|c| c != '#'), Some(1 | 2)))
         .collect::<Vec<_>>()
         .join("\n")
         .trim()
@@ -71,7 +73,7 @@ pub fn parse_usage(content: &str) -> String {
 pub fn parse_section(section: &str, content: &str) -> Option<String> {
     fn is_section_header(line: &str, section: &str) -> bool {
         line.strip_prefix("##")
-            .map_or(false, |l| l.trim().to_lowercase() == section)
+            .is_some_and(|l| l.trim().to_lowercase() == section)
     }
 
     let section = &section.to_lowercase();
@@ -98,10 +100,69 @@ pub fn parse_section(section: &str, content: &str) -> Option<String> {
     )
 }
 
+/// Render a section of the help file under a heading, as in the help
+///
+/// The lines are indented like the options and the lines of code fences are
+/// left out, so that examples in code blocks show up as plain commands.
+pub fn render_section(heading: &str, content: &str) -> String {
+    let mut out = format!("{heading}:\n");
+    for line in render_links(content)
+        .lines()
+        .filter(|l| !l.starts_with(MARKDOWN_CODE_FENCES))
+    {
+        if line.is_empty() {
+            out.push('\n');
+        } else {
+            out.push_str(&format!("  {line}\n"));
+        }
+    }
+    out
+}
+
+/// Replace inline markdown links with the link text followed by the URL
+///
+/// For example, `[GNU](https://gnu.org)` becomes `GNU (https://gnu.org)`.
+/// Anything that does not look like a complete link is left as is.
+pub fn render_links(content: &str) -> String {
+    let mut out = String::new();
+    let mut rest = content;
+    while let Some(start) = rest.find('[') {
+        let (before, link) = rest.split_at(start);
+        out.push_str(before);
+        let parsed = link[1..].split_once("](").and_then(|(text, tail)| {
+            let (url, tail) = tail.split_once(')')?;
+            let valid = !text.contains(['[', ']', '\n']) && !url.contains(char::is_whitespace);
+            valid.then_some((text, url, tail))
+        });
+        match parsed {
+            Some((text, url, tail)) => {
+                out.push_str(&format!("{text} ({url})"));
+                rest = tail;
+            }
+            None => {
+                out.push('[');
+                rest = &link[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_render_section() {
+        let content =
+            "Copy a file:\n\n```sh\ncp a b\n```\n\nSee [the manual](https://example.com).";
+        assert_eq!(
+            render_section("Examples", content),
+            "Examples:\n  Copy a file:\n\n  cp a b\n\n  See the manual (https://example.com).\n"
+        );
+    }
+
     #[test]
     fn test_parse_section() {
         let input = "\
@@ -152,6 +213,26 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_parse_section_with_block_quote() {
+        let input = "\
+            # ls\n\
+            ## after help\n\
+            Some text\n\
+            \n\
+            > Warning: this is a quote\n\
+            > spanning multiple lines\n\
+            \n\
+            ## other\n";
+
+        assert_eq!(
+            parse_section("after help", input).unwrap(),
+            "Some text\n\n\
+            > Warning: this is a quote\n\
+            > spanning multiple lines"
+        );
+    }
+
     #[test]
     fn test_parse_non_existing_section() {
         let input = "\
@@ -231,4 +312,42 @@ mod tests {
 
         assert_eq!(parse_about(input), "about a\n\nabout b");
     }
+
+    #[test]
+    fn test_parse_about_with_sub_headers() {
+        let input = "\
+            # ls\n\
+            ```\n\
+            ls -l\n\
+            ```\n\
+            \n\
+            about\n\
+            \n\
+            ### level 3 header\n\
+            \n\
+            more about\n\
+            \n\
+            #### level 4 header\n\
+            \n\
+            even more\n\
+            \n\
+            ## some section\n\
+            This is some section\n";
+
+        assert_eq!(
+            parse_about(input),
+            "about\n\n### level 3 header\n\nmore about\n\n#### level 4 header\n\neven more"
+        );
+    }
+
+    #[test]
+    fn test_render_links() {
+        assert_eq!(
+            render_links("See [the manual](https://gnu.org/ls) or [info](info:ls)."),
+            "See the manual (https://gnu.org/ls) or info (info:ls)."
+        );
+        assert_eq!(render_links("[-a] [FILE]..."), "[-a] [FILE]...");
+        assert_eq!(render_links("[a](b c)"), "[a](b c)");
+        assert_eq!(render_links("[[a](b)"), "[a (b)");
+    }
 }
@@ -12,9 +12,10 @@ mod help;
 mod help_parser;
 
 use argument::{
-    free_handling, long_handling, parse_argument, parse_arguments_attr, short_handling,
+    check_duplicate_flags, collect_help, free_handling, long_handling, operands_handling,
+    parse_argument, parse_arguments_attr, required_groups, short_handling, subcommand_handling,
 };
-use attributes::ValueAttr;
+use attributes::{Ambiguity, ValueAttr, ValueEnumAttr};
 use help::{help_handling, help_string, version_handling};
 
 use proc_macro::TokenStream;
@@ -22,7 +23,7 @@ use quote::quote;
 use syn::{parse_macro_input, Data::Enum, DeriveInput};
 
 /// Documentation for this can be found in `uutils_args`.
-#[proc_macro_derive(Arguments, attributes(arg, arguments))]
+#[proc_macro_derive(Arguments, attributes(arg, arguments, option))]
 pub fn arguments(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
 
@@ -34,37 +35,52 @@ pub fn arguments(input: TokenStream) -> TokenStream {
     };
 
     let arguments_attr = parse_arguments_attr(&input.attrs);
-    let arguments: Vec<_> = data.variants.into_iter().flat_map(parse_argument).collect();
+    let variants: Vec<_> = data.variants.iter().map(|v| v.ident.clone()).collect();
+    let variant_names = variants.iter().map(|v| v.to_string());
+    let arguments: Vec<_> = data
+        .variants
+        .into_iter()
+        .flat_map(|v| parse_argument(v, &arguments_attr))
+        .collect();
 
     let exit_code = arguments_attr.exit_code;
-    let (short, short_flags) = short_handling(&arguments);
-    let long = long_handling(&arguments, &arguments_attr.help_flags);
-    let free = free_handling(&arguments);
-    let help_string = help_string(
-        &arguments,
-        &arguments_attr.help_flags,
-        &arguments_attr.version_flags,
-        &arguments_attr.file,
-    );
-    let complete_command = complete::complete(&arguments, &arguments_attr.file);
+    let error_exit_code = arguments_attr.error_exit_code;
+    let response_files = arguments_attr.response_files;
+    let passthrough_unknown = arguments_attr.passthrough_unknown;
+    let required_groups = required_groups(&arguments, &arguments_attr.required_groups);
+    // An empty enum cannot be matched by reference
+    let variant_name = if variants.is_empty() {
+        quote!(match *self {})
+    } else {
+        quote!(match self {
+            #(Self::#variants { .. } => #variant_names,)*
+        })
+    };
+    let duplicate_flags = check_duplicate_flags(&arguments);
+    let (short, short_flags) = short_handling(&arguments, &arguments_attr);
+    let long = long_handling(&arguments, &arguments_attr);
+    let free = free_handling(&arguments, arguments_attr.suggestion_threshold);
+    let subcommand = subcommand_handling(&arguments);
+    let operands = operands_handling(&arguments);
+    let help_string = help_string(&arguments, &arguments_attr);
+    let complete_command = complete::complete(&arguments, &arguments_attr);
+    let flags_table = complete::flags_table(&arguments);
     let help = help_handling(&arguments_attr.help_flags);
     let version = version_handling(&arguments_attr.version_flags);
-    let version_string = quote!(format!(
-        "{} {}",
-        option_env!("CARGO_BIN_NAME").unwrap_or(env!("CARGO_PKG_NAME")),
-        env!("CARGO_PKG_VERSION"),
-    ));
+    let version_string = match &arguments_attr.version_extra {
+        Some(extra) => quote!(format!("{} {} {}", name, #extra, env!("CARGO_PKG_VERSION"))),
+        None => quote!(format!("{} {}", name, env!("CARGO_PKG_VERSION"))),
+    };
+    let default_name = quote!(option_env!("CARGO_BIN_NAME").unwrap_or(env!("CARGO_PKG_NAME")));
 
     // This is a bit of a hack to support `echo` and should probably not be
     // used in general.
-    let next_arg = if arguments_attr.parse_echo_style {
-        quote!(if let Some(val) = ::uutils_args::internal::echo_style_positional(parser, &[#(#short_flags),*]) {
-            Some(lexopt::Arg::Value(val))
-        } else {
-            parser.next()?
+    let echo_operands = if arguments_attr.parse_echo_style {
+        quote!(if let Some(values) = ::uutils_args::internal::echo_style_operands(parser, &[#(#short_flags),*]) {
+            return Ok(Some(::uutils_args::Argument::MultiPositional(values)));
         })
     } else {
-        quote!(parser.next()?)
+        quote!()
     };
 
     // If options_first is set and we find the first positional argument, we
@@ -81,9 +97,66 @@ pub fn arguments(input: TokenStream) -> TokenStream {
         quote!(Ok(Some(::uutils_args::Argument::Positional(value))))
     };
 
+    // The body is wrapped in a closure, so that errors can be replaced by
+    // a help or version flag found later in the arguments.
+    let body = quote!(
+        #free
+
+        #echo_operands
+
+        let arg = match parser.next()? {
+            Some(arg) => arg,
+            None => return Ok(None),
+        };
+
+        #help
+
+        #version
+
+        match arg {
+            lexopt::Arg::Short(short) => { #short },
+            lexopt::Arg::Long(long) => { #long },
+            lexopt::Arg::Value(value) => {
+                #subcommand
+                #positional
+            },
+        }
+    );
+    let body = if arguments_attr.help_short_circuits {
+        let help_flags = arguments_attr.help_flags.strings();
+        let version_flags = arguments_attr.version_flags.strings();
+        quote!(
+            #[allow(unreachable_code)]
+            let result: Result<Option<Argument<Self>>, ::uutils_args::ErrorKind> = (|| { #body })();
+            match result {
+                Err(err) => Ok(Some(
+                    ::uutils_args::internal::help_or_version_after_error(
+                        parser,
+                        &[#(#help_flags),*],
+                        &[#(#version_flags),*],
+                    )
+                    .ok_or(err)?,
+                )),
+                ok => ok,
+            }
+        )
+    } else {
+        body
+    };
+
     let expanded = quote!(
+        #duplicate_flags
+
         impl #impl_generics Arguments for #name #ty_generics #where_clause {
             const EXIT_CODE: i32 = #exit_code;
+            const ERROR_EXIT_CODE: i32 = #error_exit_code;
+            const RESPONSE_FILES: bool = #response_files;
+            const PASSTHROUGH_UNKNOWN: bool = #passthrough_unknown;
+            const REQUIRED_GROUPS: &'static [&'static [(&'static str, &'static str)]] = #required_groups;
+
+            fn variant_name(&self) -> &'static str {
+                #variant_name
+            }
 
             #[allow(unreachable_code)]
             fn next_arg(
@@ -91,32 +164,33 @@ pub fn arguments(input: TokenStream) -> TokenStream {
             ) -> Result<Option<::uutils_args::Argument<Self>>, ::uutils_args::ErrorKind> {
                 use ::uutils_args::{Value, lexopt, Error, Argument};
 
-                #free
-
-                let arg = match { #next_arg } {
-                    Some(arg) => arg,
-                    None => return Ok(None),
-                };
-
-                #help
-
-                #version
+                #body
+            }
 
-                match arg {
-                    lexopt::Arg::Short(short) => { #short },
-                    lexopt::Arg::Long(long) => { #long },
-                    lexopt::Arg::Value(value) => { #positional },
-                }
+            fn operands(operands: &[::std::ffi::OsString]) -> Result<Option<Self>, ::uutils_args::ErrorKind> {
+                #operands
             }
 
             fn help(bin_name: &str) -> String {
+                Self::help_with_name(#default_name, bin_name)
+            }
+
+            fn help_with_name(name: &str, bin_name: &str) -> String {
                 #help_string
             }
 
             fn version() -> String {
+                Self::version_with_name(#default_name)
+            }
+
+            fn version_with_name(name: &str) -> String {
                 #version_string
             }
 
+            fn flags() -> &'static [::uutils_args::flags::FlagInfo] {
+                #flags_table
+            }
+
             #[cfg(feature = "complete")]
             fn complete() -> ::uutils_args_complete::Command<'static> {
                 use ::uutils_args::Value;
@@ -140,19 +214,53 @@ pub fn value(input: TokenStream) -> TokenStream {
         panic!("Input should be an enum!");
     };
 
+    let enum_attr = match input.attrs.iter().find(|a| a.path().is_ident("value")) {
+        Some(attr) => ValueEnumAttr::parse(attr).unwrap(),
+        None => ValueEnumAttr::default(),
+    };
+
     let mut options = Vec::new();
 
     let mut match_arms = vec![];
     let mut all_keys = Vec::new();
+    let mut descriptions = Vec::new();
+    let mut other = None;
     for variant in data.variants {
         let variant_name = variant.ident.to_string();
+        // Only the first line of the doc comment fits next to a completion
+        let description = collect_help(&variant.attrs)
+            .lines()
+            .next()
+            .unwrap_or_default()
+            .to_string();
         let attrs = variant.attrs.clone();
         for attr in attrs {
             if !attr.path().is_ident("value") {
                 continue;
             }
 
-            let ValueAttr { keys, value } = ValueAttr::parse(&attr).unwrap();
+            let ValueAttr {
+                keys,
+                value,
+                other: is_other,
+            } = ValueAttr::parse(&attr).unwrap();
+
+            if is_other {
+                assert!(
+                    keys.is_empty() && value.is_none(),
+                    "A `#[value(other)]` variant cannot have keys or a value"
+                );
+                assert!(
+                    matches!(&variant.fields, syn::Fields::Unnamed(f) if f.unnamed.len() == 1),
+                    "A `#[value(other)]` variant must have a single field for the value"
+                );
+                assert!(
+                    other.is_none(),
+                    "There can only be one `#[value(other)]` variant"
+                );
+                other = Some(variant.ident.clone());
+                continue;
+            }
 
             let keys = if keys.is_empty() {
                 vec![variant_name.to_lowercase()]
@@ -161,6 +269,7 @@ pub fn value(input: TokenStream) -> TokenStream {
             };
 
             all_keys.extend(keys.clone());
+            descriptions.extend(keys.iter().map(|_| description.clone()));
             options.push(quote!(&[#(#keys),*]));
 
             let stmt = if let Some(v) = value {
@@ -176,51 +285,97 @@ pub fn value(input: TokenStream) -> TokenStream {
 
     let keys_len = all_keys.len();
 
-    let expanded = quote!(
-        impl #impl_generics Value for #name #ty_generics #where_clause {
-            fn from_value(value: &::std::ffi::OsStr) -> ::uutils_args::ValueResult<Self> {
-                let value = String::from_value(value)?;
-                let options: &[&[&str]] = &[#(#options),*];
+    // The descriptions are only given if any of the variants has one, so
+    // that shells without descriptions get the plain strings.
+    let value_hint = if descriptions.iter().all(String::is_empty) {
+        quote!(
+            let keys: [&str; #keys_len] = [#(#all_keys),*];
+            ::uutils_args_complete::ValueHint::Strings(
+                keys
+                    .into_iter()
+                    .map(ToString::to_string)
+                    .collect()
+            )
+        )
+    } else {
+        quote!(
+            let keys: [(&str, &str); #keys_len] = [#((#all_keys, #descriptions)),*];
+            ::uutils_args_complete::ValueHint::DescribedStrings(
+                keys
+                    .into_iter()
+                    .map(|(key, description)| (key.to_string(), description.to_string()))
+                    .collect()
+            )
+        )
+    };
+
+    // Without a fallback variant, values that do not match any key are an
+    // error. Otherwise, they are passed verbatim to the fallback variant.
+    let no_match = match other {
+        Some(ident) => quote!(return Ok(Self::#ident(::uutils_args::Value::from_value(raw)?))),
+        None => quote!(return Err("Invalid value".into())),
+    };
+
+    let prefix_match = match enum_attr.ambiguity {
+        Ambiguity::ExactOnly => no_match.clone(),
+        ambiguity => {
+            let candidate_arms = if ambiguity == Ambiguity::First {
+                quote!([opt, ..] => *opt,)
+            } else {
+                quote!(
+                    [opt] => *opt,
+                    _ => return Err(uutils_args::ValueError::AmbiguousValue {
+                        value: value.to_string(),
+                        candidates: candidates.iter().map(|s| s.to_string()).collect(),
+                    }.into()),
+                )
+            };
+            quote!(
+                // Abbreviations are only inferred for valid UTF-8.
+                let Some(value) = raw.to_str() else {
+                    #no_match
+                };
                 let mut candidates: Vec<&str> = Vec::new();
-                let mut exact_match: Option<&str> = None;
-
-                'outer: for &opt in options {
-                    'inner: for &o in opt {
-                        if value == o {
-                            exact_match = Some(o);
-                            break 'outer;
-                        } else if o.starts_with(&value) {
-                            candidates.push(o);
-                            break 'inner;
-                        }
+                for &opt in options {
+                    if let Some(&o) = opt.iter().find(|o| o.starts_with(value)) {
+                        candidates.push(o);
                     }
                 }
+                match &candidates[..] {
+                    [] => #no_match,
+                    #candidate_arms
+                }
+            )
+        }
+    };
 
-                let opt = match (exact_match, &candidates[..]) {
-                    (Some(opt), _) => opt,
-                    (None, [opt]) => opt,
-                    (None, []) => return Err("Invalid value".into()),
-                    (None, opts) => return Err(uutils_args::ValueError::AmbiguousValue {
-                        value,
-                        candidates: candidates.iter().map(|s| s.to_string()).collect(),
-                    }.into())
+    let expanded = quote!(
+        impl #impl_generics Value for #name #ty_generics #where_clause {
+            fn from_value(raw: &::std::ffi::OsStr) -> ::uutils_args::ValueResult<Self> {
+                let options: &[&[&str]] = &[#(#options),*];
+
+                // Exact matches are compared as bytes, so a value that is
+                // not valid UTF-8 is just a value that does not match.
+                let exact_match = options
+                    .iter()
+                    .flat_map(|opt| opt.iter())
+                    .copied()
+                    .find(|o| o.as_bytes() == raw.as_encoded_bytes());
+
+                let opt = match exact_match {
+                    Some(opt) => opt,
+                    None => { #prefix_match }
                 };
 
                 Ok(match opt {
                     #(#match_arms)*
-                    _ => unreachable!("Should be caught by (None, []) case above.")
+                    _ => unreachable!("Only keys of the variants are matched above.")
                 })
             }
 
             #[cfg(feature = "complete")]
             fn value_hint() -> ::uutils_args_complete::ValueHint {
-                let keys: [&str; #keys_len] = [#(#all_keys),*];
-                ::uutils_args_complete::ValueHint::Strings(
-                    keys
-                        .into_iter()
-                        .map(ToString::to_string)
-                        .collect()
-                )
+                #value_hint
             }
         }
     );
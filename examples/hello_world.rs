@@ -36,8 +36,7 @@ fn main() -> Result<(), uutils_args::Error> {
         name: String::new(),
         count: 1,
     }
-    .parse(std::env::args_os())
-    .unwrap();
+    .parse_or_exit(std::env::args_os());
 
     for _ in 0..settings.count {
         println!("Hello, {}!", settings.name);
@@ -33,6 +33,6 @@ impl Options<Arg> for Settings {
 }
 
 fn main() {
-    let (settings, _operands) = Settings::default().parse(std::env::args_os()).unwrap();
+    let (settings, _operands) = Settings::default().parse_or_exit(std::env::args_os());
     println!("{:?}", settings.color);
 }
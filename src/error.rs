@@ -1,6 +1,7 @@
 // For the full copyright and license information, please view the LICENSE
 // file that was distributed with this source code.
 
+use crate::Arguments;
 use std::{
     error::Error as StdError,
     ffi::OsString,
@@ -30,6 +31,17 @@ pub enum ErrorKind {
     /// No more positional arguments were expected, but one was given anyway.
     UnexpectedArgument(String),
 
+    /// More positional arguments were given than the signature accepts.
+    ///
+    /// `expected` is the maximum number of arguments that the signature
+    /// accepts, `got` the number of arguments that were given and
+    /// `first_extra` is the first argument that could not be assigned.
+    TooManyPositionalArguments {
+        expected: usize,
+        got: usize,
+        first_extra: OsString,
+    },
+
     /// A value was passed to an option that didn't expect a value.
     UnexpectedValue {
         option: String,
@@ -54,6 +66,179 @@ pub enum ErrorKind {
     NonUnicodeValue(OsString),
 
     IoError(std::io::Error),
+
+    /// A response file, given as `@file` with
+    /// `#[arguments(response_files)]`, could not be read.
+    ///
+    /// This is also returned if response files are nested too deeply.
+    ResponseFile {
+        file: OsString,
+        error: std::io::Error,
+    },
+
+    /// The parsed options were rejected by
+    /// [`Options::validate`](crate::Options::validate), for example because
+    /// two options cannot be combined.
+    Validation(String),
+
+    /// None of the options of a `required_group` was given.
+    ///
+    /// This contains the options of the group.
+    MissingRequiredGroup(Vec<String>),
+
+    /// More than one of the options of a `required_group` was given.
+    ///
+    /// This contains the options of the group that were given.
+    TooManyInGroup(Vec<String>),
+
+    /// The help was requested with a help flag.
+    ///
+    /// This contains the help text to print. The exit code of this error is
+    /// 0, because it does not indicate a failure.
+    HelpRequested(String),
+
+    /// The version was requested with a version flag.
+    ///
+    /// This contains the version text to print. Like
+    /// [`ErrorKind::HelpRequested`], the exit code of this error is 0.
+    VersionRequested(String),
+}
+
+/// The category of an [`ErrorKind`], without any of its data
+///
+/// This allows branching on the kind of error without matching on the
+/// fields of each variant. New codes might be added in the future.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ErrorCode {
+    MissingValue,
+    MissingPositionalArguments,
+    UnexpectedOption,
+    UnexpectedArgument,
+    TooManyPositionalArguments,
+    UnexpectedValue,
+    ParsingFailed,
+    AmbiguousOption,
+    NonUnicodeValue,
+    IoError,
+    ResponseFile,
+    Validation,
+    MissingRequiredGroup,
+    TooManyInGroup,
+    HelpRequested,
+    VersionRequested,
+}
+
+impl ErrorKind {
+    /// The [`ErrorCode`] corresponding to this error
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            ErrorKind::MissingValue { .. } => ErrorCode::MissingValue,
+            ErrorKind::MissingPositionalArguments(_) => ErrorCode::MissingPositionalArguments,
+            ErrorKind::UnexpectedOption(..) => ErrorCode::UnexpectedOption,
+            ErrorKind::UnexpectedArgument(_) => ErrorCode::UnexpectedArgument,
+            ErrorKind::TooManyPositionalArguments { .. } => ErrorCode::TooManyPositionalArguments,
+            ErrorKind::UnexpectedValue { .. } => ErrorCode::UnexpectedValue,
+            ErrorKind::ParsingFailed { .. } => ErrorCode::ParsingFailed,
+            ErrorKind::AmbiguousOption { .. } => ErrorCode::AmbiguousOption,
+            ErrorKind::NonUnicodeValue(_) => ErrorCode::NonUnicodeValue,
+            ErrorKind::IoError(_) => ErrorCode::IoError,
+            ErrorKind::ResponseFile { .. } => ErrorCode::ResponseFile,
+            ErrorKind::Validation(_) => ErrorCode::Validation,
+            ErrorKind::MissingRequiredGroup(_) => ErrorCode::MissingRequiredGroup,
+            ErrorKind::TooManyInGroup(_) => ErrorCode::TooManyInGroup,
+            ErrorKind::HelpRequested(_) => ErrorCode::HelpRequested,
+            ErrorKind::VersionRequested(_) => ErrorCode::VersionRequested,
+        }
+    }
+
+    /// Whether parsing can continue with the next argument after this error
+    ///
+    /// Invalid options and values only affect the argument they were found
+    /// in, so they are recoverable. A missing value can only occur at the
+    /// end of the arguments and errors about operands or I/O affect the
+    /// arguments as a whole, so they are fatal.
+    pub fn is_recoverable(&self) -> bool {
+        match self {
+            ErrorKind::UnexpectedOption(..)
+            | ErrorKind::UnexpectedValue { .. }
+            | ErrorKind::ParsingFailed { .. }
+            | ErrorKind::AmbiguousOption { .. }
+            | ErrorKind::NonUnicodeValue(_) => true,
+            ErrorKind::MissingValue { .. }
+            | ErrorKind::MissingPositionalArguments(_)
+            | ErrorKind::UnexpectedArgument(_)
+            | ErrorKind::TooManyPositionalArguments { .. }
+            | ErrorKind::IoError(_)
+            | ErrorKind::ResponseFile { .. }
+            | ErrorKind::Validation(_)
+            | ErrorKind::MissingRequiredGroup(_)
+            | ErrorKind::TooManyInGroup(_)
+            | ErrorKind::HelpRequested(_)
+            | ErrorKind::VersionRequested(_) => false,
+        }
+    }
+
+    /// Whether this error is caused by invalid arguments
+    ///
+    /// Usage errors exit with [`Arguments::EXIT_CODE`](crate::Arguments::EXIT_CODE)
+    /// and other errors, like I/O errors, with
+    /// [`Arguments::ERROR_EXIT_CODE`](crate::Arguments::ERROR_EXIT_CODE). The
+    /// help and version are not errors and therefore not usage errors.
+    pub fn is_usage_error(&self) -> bool {
+        match self {
+            ErrorKind::MissingValue { .. }
+            | ErrorKind::MissingPositionalArguments(_)
+            | ErrorKind::UnexpectedOption(..)
+            | ErrorKind::UnexpectedArgument(_)
+            | ErrorKind::TooManyPositionalArguments { .. }
+            | ErrorKind::UnexpectedValue { .. }
+            | ErrorKind::ParsingFailed { .. }
+            | ErrorKind::AmbiguousOption { .. }
+            | ErrorKind::NonUnicodeValue(_)
+            | ErrorKind::Validation(_)
+            | ErrorKind::MissingRequiredGroup(_)
+            | ErrorKind::TooManyInGroup(_) => true,
+            ErrorKind::IoError(_)
+            | ErrorKind::ResponseFile { .. }
+            | ErrorKind::HelpRequested(_)
+            | ErrorKind::VersionRequested(_) => false,
+        }
+    }
+}
+
+impl Error {
+    /// Create an error with the exit code that `T` uses for this kind
+    ///
+    /// Usage errors get [`Arguments::EXIT_CODE`] and other errors
+    /// [`Arguments::ERROR_EXIT_CODE`]. This is useful for errors that are
+    /// found after parsing, like those of [`Unpack`](crate::positional::Unpack).
+    pub fn from_kind<T: Arguments>(kind: ErrorKind) -> Self {
+        let exit_code = if kind.is_usage_error() {
+            T::EXIT_CODE
+        } else {
+            T::ERROR_EXIT_CODE
+        };
+        Self { exit_code, kind }
+    }
+
+    /// Print the error and exit with its exit code
+    ///
+    /// The help and version are printed to stdout and all other errors are
+    /// printed to stderr. With the `color` feature, the flags in the help are
+    /// styled if stdout is a terminal. This requires the `std-io` feature.
+    #[cfg(feature = "std-io")]
+    pub fn exit(&self) -> ! {
+        match &self.kind {
+            ErrorKind::HelpRequested(s) if crate::internal::use_color() => {
+                print!("{}", crate::internal::style_help(s));
+            }
+            ErrorKind::HelpRequested(s) => print!("{s}"),
+            ErrorKind::VersionRequested(s) => println!("{s}"),
+            _ => eprintln!("{self}"),
+        }
+        std::process::exit(self.exit_code)
+    }
 }
 
 impl From<std::io::Error> for ErrorKind {
@@ -84,7 +269,20 @@ impl Debug for ErrorKind {
 
 impl Display for ErrorKind {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // These are not really errors, so they are displayed as is.
+        if let ErrorKind::HelpRequested(s) | ErrorKind::VersionRequested(s) = self {
+            return write!(f, "{s}");
+        }
+
+        // GNU prefixes its messages with the name of the program instead,
+        // which is left to the program. The other errors keep their prefix.
+        if cfg!(feature = "gnu-errors") {
+            if let Some(result) = fmt_gnu(self, f) {
+                return result;
+            }
+        }
         write!(f, "error: ")?;
+
         match self {
             ErrorKind::MissingValue { option } => match option {
                 Some(option) => write!(f, "Missing value for '{option}'."),
@@ -107,6 +305,17 @@ impl Display for ErrorKind {
             ErrorKind::UnexpectedArgument(arg) => {
                 write!(f, "Found an invalid argument '{}'.", arg)
             }
+            ErrorKind::TooManyPositionalArguments {
+                expected,
+                got,
+                first_extra,
+            } => {
+                write!(
+                    f,
+                    "Expected at most {expected} positional argument(s), but got {got}. The first unexpected argument is '{}'.",
+                    first_extra.to_string_lossy()
+                )
+            }
             ErrorKind::UnexpectedValue { option, value } => {
                 write!(
                     f,
@@ -119,6 +328,9 @@ impl Display for ErrorKind {
                 value,
                 error,
             } => {
+                if let Some(custom) = error.downcast_ref::<CustomMessage>() {
+                    return write!(f, "{custom}");
+                }
                 // TODO: option should not not be Option<String>, because even for positional
                 // arguments we want to specify the name of the value.
                 if option.is_empty() {
@@ -141,10 +353,93 @@ impl Display for ErrorKind {
                 write!(f, "Invalid unicode value found: {}", x.to_string_lossy())
             }
             ErrorKind::IoError(x) => std::fmt::Display::fmt(x, f),
+            ErrorKind::ResponseFile { file, error } => {
+                write!(
+                    f,
+                    "Could not read response file '{}': {error}",
+                    file.to_string_lossy()
+                )
+            }
+            ErrorKind::Validation(message) => write!(f, "{message}"),
+            ErrorKind::MissingRequiredGroup(options) => {
+                write!(f, "One of the following options is required:")?;
+                for option in options {
+                    write!(f, " '{option}'")?;
+                }
+                Ok(())
+            }
+            ErrorKind::TooManyInGroup(options) => {
+                write!(f, "Only one of the following options can be given:")?;
+                for option in options {
+                    write!(f, " '{option}'")?;
+                }
+                Ok(())
+            }
+            ErrorKind::HelpRequested(_) | ErrorKind::VersionRequested(_) => unreachable!(),
         }
     }
 }
 
+/// The error of [`ErrorKind::ParsingFailed`] for an option with an `error`
+/// template, which replaces the whole message
+#[derive(Debug)]
+pub(crate) struct CustomMessage {
+    pub(crate) message: String,
+    pub(crate) error: Box<dyn StdError + Send + Sync + 'static>,
+}
+
+impl Display for CustomMessage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl StdError for CustomMessage {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        Some(&*self.error)
+    }
+}
+
+/// Format the errors that GNU getopt and coreutils report with their exact
+/// wording
+///
+/// Returns `None` for the errors that GNU has no standard message for.
+fn fmt_gnu(kind: &ErrorKind, f: &mut std::fmt::Formatter<'_>) -> Option<std::fmt::Result> {
+    Some(match kind {
+        ErrorKind::MissingValue {
+            option: Some(option),
+        } => match option.strip_prefix("--") {
+            Some(_) => write!(f, "option '{option}' requires an argument"),
+            None => write!(
+                f,
+                "option requires an argument -- '{}'",
+                option.trim_start_matches('-')
+            ),
+        },
+        ErrorKind::MissingPositionalArguments(args) if !args.is_empty() => {
+            write!(f, "missing {} operand", args[0].to_lowercase())
+        }
+        ErrorKind::UnexpectedOption(option, _) => {
+            if option.starts_with("--") {
+                write!(f, "unrecognized option '{option}'")
+            } else if let Some(short) = option.strip_prefix('-') {
+                write!(f, "invalid option -- '{short}'")
+            } else {
+                // dd-style arguments, like `if=FILE`
+                write!(f, "unrecognized operand '{option}'")
+            }
+        }
+        ErrorKind::AmbiguousOption { option, candidates } => {
+            write!(f, "option '--{option}' is ambiguous; possibilities:").and_then(|()| {
+                candidates
+                    .iter()
+                    .try_for_each(|candidate| write!(f, " '--{candidate}'"))
+            })
+        }
+        _ => return None,
+    })
+}
+
 impl From<lexopt::Error> for ErrorKind {
     fn from(other: lexopt::Error) -> ErrorKind {
         match other {
@@ -0,0 +1,77 @@
+// For the full copyright and license information, please view the LICENSE
+// file that was distributed with this source code.
+
+//! Runtime information about the flags of an [`Arguments`](crate::Arguments)
+//! type.
+//!
+//! The table is generated by the derive macro and returned by
+//! [`Arguments::flags`](crate::Arguments::flags). Unlike the information for
+//! completions, it is available without the `complete` feature.
+
+/// Information about a single option
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FlagInfo {
+    /// The short flags, without the leading `-`
+    pub short: &'static [Flag],
+    /// The long flags, without the leading `--`
+    pub long: &'static [Flag],
+    /// The help text for the option
+    pub help: &'static str,
+    /// Whether the option is hidden from the help
+    pub hidden_help: bool,
+    /// Whether the option is hidden from the completions
+    pub hidden_complete: bool,
+}
+
+/// A single flag of an option
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Flag {
+    pub flag: &'static str,
+    pub value: FlagValue,
+    /// Whether the flag is an alias, which is accepted but not shown in the
+    /// help and completions
+    pub alias: bool,
+}
+
+/// Whether a flag takes a value
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FlagValue {
+    /// The flag does not take a value
+    No,
+    /// The flag takes an optional value with the given name
+    Optional(&'static str),
+    /// The flag requires a value with the given name
+    Required(&'static str),
+}
+
+#[cfg(feature = "complete")]
+impl FlagInfo {
+    /// Convert to the argument used for completions, with the given hint
+    pub(crate) fn to_complete(
+        self,
+        hint: Option<uutils_args_complete::ValueHint>,
+    ) -> uutils_args_complete::Arg<'static> {
+        let flags = |flags: &'static [Flag]| {
+            flags
+                .iter()
+                .filter(|f| !f.alias)
+                .map(|f| uutils_args_complete::Flag {
+                    flag: f.flag,
+                    value: match f.value {
+                        FlagValue::No => uutils_args_complete::Value::No,
+                        FlagValue::Optional(name) => uutils_args_complete::Value::Optional(name),
+                        FlagValue::Required(name) => uutils_args_complete::Value::Required(name),
+                    },
+                })
+                .collect()
+        };
+        uutils_args_complete::Arg {
+            short: flags(self.short),
+            long: flags(self.long),
+            help: self.help,
+            value: hint,
+            hidden_help: self.hidden_help,
+            hidden_complete: self.hidden_complete,
+        }
+    }
+}
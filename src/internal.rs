@@ -10,27 +10,48 @@
 //! Yet, they should be properly documented to make macro-expanded code
 //! readable.
 
-use crate::error::ErrorKind;
-use crate::value::Value;
+use crate::error::{CustomMessage, ErrorKind};
+use crate::value::{Value, ValueResult};
+use crate::{Argument, Arguments};
 use std::{
     ffi::{OsStr, OsString},
-    fmt::Write,
+    fmt::{Debug, Write},
+    ops::RangeBounds,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex,
+    },
 };
 
-/// Parses an echo-style positional argument
+/// The maximum number of suggestions returned by [`filter_suggestions`]
+const MAX_SUGGESTIONS: usize = 3;
+
+/// Whether warnings for deprecated options should be printed
+pub(crate) static DEPRECATION_WARNINGS: AtomicBool = AtomicBool::new(true);
+
+/// A function applied to the version string before it is printed
+pub(crate) type VersionHook = fn(String) -> String;
+
+/// The hook set with [`crate::set_version_hook`]
+pub(crate) static VERSION_HOOK: Mutex<Option<VersionHook>> = Mutex::new(None);
+
+/// The deprecated options for which a warning has already been printed
+static WARNED_DEPRECATED: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+/// Parses the operands of an echo-style command
 ///
 /// This means that any argument that does not solely consist of a hyphen
 /// followed by the characters in the list of `short_args` is considered
 /// to be a positional argument, instead of an invalid argument. This
-/// includes the `--` argument, which is ignored by `echo`.
-pub fn echo_style_positional(p: &mut lexopt::Parser, short_args: &[char]) -> Option<OsString> {
-    let mut raw = p.try_raw_args()?;
+/// includes the `--` argument, which is not special for `echo`. Once such an
+/// argument is found, it is returned together with all remaining arguments,
+/// because `echo` does not parse options after the first operand.
+pub fn echo_style_operands(p: &mut lexopt::Parser, short_args: &[char]) -> Option<Vec<OsString>> {
+    let raw = p.try_raw_args()?;
     let val = raw.peek()?;
 
     if is_echo_style_positional(val, short_args) {
-        let val = val.into();
-        raw.next();
-        Some(val)
+        Some(raw.collect())
     } else {
         None
     }
@@ -43,9 +64,110 @@ fn is_echo_style_positional(s: &OsStr, short_args: &[char]) -> bool {
         // be a positional argument.
         None => return true,
     };
-    let mut chars = s.chars();
-    let is_short_args = chars.next() == Some('-') && chars.all(|c| short_args.contains(&c));
-    !is_short_args
+    // A single hyphen is an operand, like any argument that does not start
+    // with a hyphen.
+    match s.strip_prefix('-') {
+        Some(flags) if !flags.is_empty() => !flags.chars().all(|c| short_args.contains(&c)),
+        _ => true,
+    }
+}
+
+/// Get the value of an option, but do not take a separate argument starting
+/// with a hyphen
+///
+/// A value attached to the option, like in `--target=-r` or `-t-r`, is
+/// always accepted. A separate argument like the `-r` in `--target -r` is
+/// more likely to be a mistake, so it results in
+/// [`ErrorKind::MissingValue`] and is not consumed. A single hyphen is
+/// still accepted, because it usually means stdin or stdout.
+pub fn value_without_dash(p: &mut lexopt::Parser, option: &str) -> Result<OsString, ErrorKind> {
+    if let Some(value) = p.optional_value() {
+        return Ok(value);
+    }
+    let looks_like_option = p
+        .try_raw_args()
+        .and_then(|raw| {
+            raw.peek()
+                .map(|v| v.as_encoded_bytes().starts_with(b"-") && v != "-")
+        })
+        .unwrap_or(false);
+    if looks_like_option {
+        return Err(ErrorKind::MissingValue {
+            option: Some(option.into()),
+        });
+    }
+    Ok(p.value()?)
+}
+
+/// Collect the values of a greedy option
+///
+/// These are the value attached to the option, like in `--exec=cmd`, and all
+/// following arguments up to a `--` or the end. The `--` is not consumed, so
+/// the arguments after it are still operands. At least one value is required.
+pub fn greedy_values<T>(
+    p: &mut lexopt::Parser,
+    option: &str,
+    mut parse: impl FnMut(&OsStr) -> Result<T, ErrorKind>,
+) -> Result<Vec<T>, ErrorKind> {
+    let mut values = Vec::new();
+    if let Some(value) = p.optional_value() {
+        values.push(parse(&value)?);
+    }
+    if let Some(mut raw) = p.try_raw_args() {
+        while let Some(value) = raw.next_if(|v| v != "--") {
+            values.push(parse(&value)?);
+        }
+    }
+    if values.is_empty() {
+        return Err(ErrorKind::MissingValue {
+            option: Some(option.into()),
+        });
+    }
+    Ok(values)
+}
+
+/// Return an error if a value is attached to an option that takes none,
+/// like in `--verbose=yes`
+pub fn reject_value(p: &mut lexopt::Parser, option: &str) -> Result<(), ErrorKind> {
+    match p.optional_value() {
+        Some(value) => Err(ErrorKind::UnexpectedValue {
+            option: option.into(),
+            value,
+        }),
+        None => Ok(()),
+    }
+}
+
+/// End the options after an option marked as `terminator`
+///
+/// The parser is replaced by one that returns all remaining arguments as
+/// operands. The rest of a cluster of short options, like the `x` in `-ex`,
+/// is an operand too, with a hyphen in front of it. A value attached to a
+/// long option that does not take one is still an error.
+pub fn end_options(p: &mut lexopt::Parser, option: &str) -> Result<(), ErrorKind> {
+    let mut rest = Vec::new();
+    if let Some(value) = p.optional_value() {
+        if option.starts_with("--") {
+            return Err(ErrorKind::UnexpectedValue {
+                option: option.into(),
+                value,
+            });
+        }
+        let mut cluster = OsString::from("-");
+        cluster.push(value);
+        rest.push(cluster);
+    }
+    // The pending value was taken above, so the raw arguments are available.
+    rest.extend(p.raw_args()?);
+
+    // A new parser sees the `--` first and treats everything after it as
+    // operands, just like the user had typed it.
+    let args = std::iter::once(OsString::from("--")).chain(rest);
+    *p = match p.bin_name() {
+        Some(bin_name) => lexopt::Parser::from_iter(std::iter::once(bin_name.into()).chain(args)),
+        None => lexopt::Parser::from_args(args),
+    };
+    Ok(())
 }
 
 /// Parse an argument defined by a prefix
@@ -66,35 +188,160 @@ pub fn parse_prefix<T: Value>(parser: &mut lexopt::Parser, prefix: &'static str)
 
 /// Parse a value and wrap the error into an `Error::ParsingFailed`
 pub fn parse_value_for_option<T: Value>(opt: &str, v: &OsStr) -> Result<T, ErrorKind> {
-    T::from_value(v).map_err(|e| ErrorKind::ParsingFailed {
+    T::from_value_named(v, opt).map_err(|e| ErrorKind::ParsingFailed {
+        option: opt.into(),
+        value: v.to_string_lossy().to_string(),
+        error: e,
+    })
+}
+
+/// Parse a value with a custom parser, given with `parser = ...`, instead
+/// of [`Value`]
+pub fn parse_value_with<T>(
+    opt: &str,
+    v: &OsStr,
+    parser: impl FnOnce(&OsStr) -> ValueResult<T>,
+) -> Result<T, ErrorKind> {
+    parser(v).map_err(|e| ErrorKind::ParsingFailed {
         option: opt.into(),
         value: v.to_string_lossy().to_string(),
         error: e,
     })
 }
 
+/// Parse a value like [`parse_value_for_option`] and check that it is in
+/// `range`
+pub fn parse_value_in_range<T, R>(opt: &str, v: &OsStr, range: R) -> Result<T, ErrorKind>
+where
+    T: Value + PartialOrd,
+    R: RangeBounds<T> + Debug,
+{
+    let value = parse_value_for_option(opt, v)?;
+    check_range(opt, v, value, range)
+}
+
+/// Check that the `value` parsed from `v` is in `range`
+pub fn check_range<T, R>(opt: &str, v: &OsStr, value: T, range: R) -> Result<T, ErrorKind>
+where
+    T: PartialOrd,
+    R: RangeBounds<T> + Debug,
+{
+    if range.contains(&value) {
+        return Ok(value);
+    }
+    let v = v.to_string_lossy().to_string();
+    Err(ErrorKind::ParsingFailed {
+        option: opt.into(),
+        error: format!("value {v} out of range {range:?}").into(),
+        value: v,
+    })
+}
+
+/// Replace the message of an [`ErrorKind::ParsingFailed`] error with the
+/// template given with `error = "..."`
+///
+/// The placeholders `{value}`, `{option}` and `{error}` in the template are
+/// replaced with the value, the option and the original error. Like in
+/// `format!`, `{{` and `}}` are escaped braces. The derive macro rejects
+/// other placeholders.
+pub fn with_error_template<T>(
+    result: Result<T, ErrorKind>,
+    template: &str,
+) -> Result<T, ErrorKind> {
+    result.map_err(|kind| {
+        let ErrorKind::ParsingFailed {
+            option,
+            value,
+            error,
+        } = kind
+        else {
+            return kind;
+        };
+        let error_message = error.to_string();
+        let placeholders = [
+            ("{value}", value.as_str()),
+            ("{option}", option.as_str()),
+            ("{error}", error_message.as_str()),
+        ];
+        let mut message = String::new();
+        let mut rest = template;
+        while let Some(start) = rest.find(['{', '}']) {
+            message.push_str(&rest[..start]);
+            rest = &rest[start..];
+            if rest.starts_with("{{") || rest.starts_with("}}") {
+                message.push_str(&rest[..1]);
+                rest = &rest[2..];
+            } else if let Some((placeholder, replacement)) =
+                placeholders.iter().find(|(p, _)| rest.starts_with(p))
+            {
+                message.push_str(replacement);
+                rest = &rest[placeholder.len()..];
+            } else {
+                message.push_str(&rest[..1]);
+                rest = &rest[1..];
+            }
+        }
+        message.push_str(rest);
+        ErrorKind::ParsingFailed {
+            option,
+            value,
+            error: Box::new(CustomMessage { message, error }),
+        }
+    })
+}
+
 /// Expand unambiguous prefixes to a list of candidates
+///
+/// If `case_insensitive` is set, both the input and the options are
+/// lowercased before they are compared. An option matching the input
+/// exactly is still preferred over one that only matches without case.
+///
+/// If no option matches, the error contains the options that are more
+/// similar to the input than `suggestion_threshold`.
 pub fn infer_long_option<'a>(
     input: &'a str,
     long_options: &'a [&'a str],
+    case_insensitive: bool,
+    suggestion_threshold: f64,
 ) -> Result<&'a str, ErrorKind> {
+    if case_insensitive {
+        if let Some(opt) = long_options.iter().find(|opt| **opt == input) {
+            return Ok(opt);
+        }
+    }
+
+    let normalize = |s: &str| {
+        if case_insensitive {
+            s.to_lowercase()
+        } else {
+            s.to_string()
+        }
+    };
+    let normalized_input = normalize(input);
+
     let mut candidates = Vec::new();
     let mut exact_match = None;
     for opt in long_options {
-        if *opt == input {
+        let normalized_opt = normalize(opt);
+        if normalized_opt == normalized_input {
             exact_match = Some(opt);
             break;
-        } else if opt.starts_with(input) {
-            candidates.push(opt);
+        } else if normalized_opt.starts_with(&normalized_input) {
+            candidates.push(*opt);
         }
     }
 
+    // Sorted and deduplicated, so that a long option that is registered
+    // twice is not ambiguous with itself and the error is deterministic.
+    candidates.sort_unstable();
+    candidates.dedup();
+
     match (exact_match, &candidates[..]) {
         (Some(opt), _) => Ok(*opt),
-        (None, [opt]) => Ok(**opt),
+        (None, [opt]) => Ok(*opt),
         (None, []) => Err(ErrorKind::UnexpectedOption(
             format!("--{input}"),
-            filter_suggestions(input, long_options, "--"),
+            filter_suggestions(input, long_options, "--", suggestion_threshold),
         )),
         (None, _) => Err(ErrorKind::AmbiguousOption {
             option: input.to_string(),
@@ -104,25 +351,256 @@ pub fn infer_long_option<'a>(
 }
 
 /// Filter a list of options to just the elements that are similar to the given string
-pub fn filter_suggestions(input: &str, long_options: &[&str], prefix: &str) -> Vec<String> {
-    long_options
+///
+/// The options are sorted by similarity, with the most similar option
+/// first, and at most `MAX_SUGGESTIONS` options are returned.
+pub fn filter_suggestions(
+    input: &str,
+    long_options: &[&str],
+    prefix: &str,
+    threshold: f64,
+) -> Vec<String> {
+    let mut suggestions: Vec<_> = long_options
         .iter()
-        .filter(|opt| strsim::jaro(input, opt) > 0.7)
-        .map(|o| format!("{prefix}{o}"))
+        .map(|opt| (strsim::jaro(input, opt), opt))
+        .filter(|(similarity, _)| *similarity > threshold)
+        .collect();
+
+    // The sort is stable, so equally similar options keep their order.
+    suggestions.sort_by(|(a, _), (b, _)| b.total_cmp(a));
+
+    suggestions
+        .into_iter()
+        .take(MAX_SUGGESTIONS)
+        .map(|(_, o)| format!("{prefix}{o}"))
         .collect()
 }
 
+/// Suggest alternatives for an unknown short option
+///
+/// A short option that only differs in case from the unknown option is
+/// suggested first. If the unknown option is followed by more characters,
+/// the user might have meant a long option with a single hyphen (e.g.
+/// `-color` for `--color`), so similar long options are suggested too.
+///
+/// The rest of the current argument is consumed, so this should only be
+/// called when the parsing fails.
+pub fn short_suggestions(
+    parser: &mut lexopt::Parser,
+    short: char,
+    short_options: &[char],
+    long_options: &[&str],
+    threshold: f64,
+) -> Vec<String> {
+    let mut suggestions: Vec<String> = short_options
+        .iter()
+        .filter(|c| **c != short && c.eq_ignore_ascii_case(&short))
+        .map(|c| format!("-{c}"))
+        .collect();
+
+    let rest = parser.optional_value();
+    if let Some(rest) = rest.as_ref().and_then(|r| r.to_str()) {
+        let input = format!("{short}{rest}");
+        suggestions.extend(filter_suggestions(&input, long_options, "--", threshold));
+    }
+
+    suggestions.truncate(MAX_SUGGESTIONS);
+    suggestions
+}
+
+/// Find a help or version flag in the remaining arguments
+///
+/// This is used to let these flags take precedence over an error in an
+/// earlier argument. The remaining arguments are consumed.
+pub fn help_or_version_after_error<T: Arguments>(
+    parser: &mut lexopt::Parser,
+    help_flags: &[&str],
+    version_flags: &[&str],
+) -> Option<Argument<T>> {
+    // Discard the rest of a partially parsed argument.
+    let _ = parser.optional_value();
+
+    for arg in parser.raw_args().ok()? {
+        match arg.to_str() {
+            Some("--") => break,
+            Some(arg) if help_flags.contains(&arg) => return Some(Argument::Help),
+            Some(arg) if version_flags.contains(&arg) => return Some(Argument::Version),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Wrap an argument parsed by a subcommand into the argument type of the
+/// parent command.
+///
+/// Subcommands cannot be nested, so a subcommand of a subcommand is treated
+/// as a regular positional argument.
+pub fn wrap_subcommand_argument<S: Arguments, T: Arguments>(
+    arg: Argument<S>,
+    wrap: fn(S) -> T,
+) -> Argument<T> {
+    match arg {
+        Argument::Help => Argument::Help,
+        Argument::Version => Argument::Version,
+        Argument::Positional(arg) => Argument::Positional(arg),
+        Argument::MultiPositional(args) => Argument::MultiPositional(args),
+        Argument::Custom(arg) => Argument::Custom(wrap(arg)),
+        Argument::Unknown(arg) => Argument::Unknown(arg),
+        Argument::Subcommand { name, .. } => Argument::Positional(name),
+    }
+}
+
+/// The unknown long option as it was given, including a value attached
+/// with `=`
+pub fn unknown_long_option(parser: &mut lexopt::Parser, option: String) -> OsString {
+    let mut option = OsString::from(option);
+    if let Some(value) = parser.optional_value() {
+        option.push("=");
+        option.push(value);
+    }
+    option
+}
+
+/// Print a warning that a deprecated option was used
+///
+/// The warning is printed at most once per option and not at all if the
+/// warnings have been disabled with [`crate::set_deprecation_warnings`] or
+/// the `std-io` feature is disabled. Which options have been warned about is
+/// tracked for the whole process, not per parse, so parsing the same
+/// arguments again does not repeat the warning.
+pub fn warn_deprecated(parser: &lexopt::Parser, option: &str, message: &str) {
+    if cfg!(feature = "std-io") && DEPRECATION_WARNINGS.load(Ordering::Relaxed) {
+        let bin_name = parser.bin_name().unwrap_or_default();
+        let _ = write_deprecation_warning(&mut std::io::stderr(), bin_name, option, message);
+    }
+}
+
+/// Write the warning for a deprecated option if it is the first use of it
+fn write_deprecation_warning(
+    w: &mut impl std::io::Write,
+    bin_name: &str,
+    option: &str,
+    message: &str,
+) -> std::io::Result<()> {
+    if first_deprecated_use(option) {
+        writeln!(
+            w,
+            "{bin_name}: warning: option '{option}' is deprecated: {message}"
+        )?;
+    }
+    Ok(())
+}
+
+fn first_deprecated_use(option: &str) -> bool {
+    let mut warned = WARNED_DEPRECATED.lock().unwrap();
+    if warned.iter().any(|o| o == option) {
+        false
+    } else {
+        warned.push(option.into());
+        true
+    }
+}
+
+/// The width of the terminal in columns
+///
+/// The `COLUMNS` environment variable takes precedence. Otherwise, if the
+/// `terminal-width` feature is enabled, the width of the terminal attached
+/// to stdout is used.
+pub fn terminal_width() -> Option<usize> {
+    let columns = std::env::var("COLUMNS").ok().and_then(|c| c.parse().ok());
+    if let Some(columns @ 1..) = columns {
+        return Some(columns);
+    }
+
+    #[cfg(feature = "terminal-width")]
+    if let Some((terminal_size::Width(width), _)) = terminal_size::terminal_size() {
+        return Some(width.into());
+    }
+
+    None
+}
+
+/// Whether the help printed to stdout is styled with colors
+///
+/// This requires the `color` feature. The help is then styled if stdout is
+/// a terminal, unless the `NO_COLOR` environment variable is set to a
+/// non-empty value. This is only checked where the help is printed, because
+/// the help can also be written elsewhere, e.g. with
+/// [`Options::parse_capturing`](crate::Options::parse_capturing).
+pub fn use_color() -> bool {
+    use std::io::IsTerminal;
+    cfg!(feature = "color")
+        && std::env::var_os("NO_COLOR").unwrap_or_default().is_empty()
+        && std::io::stdout().is_terminal()
+}
+
 /// Print a formatted list of options.
+///
+/// The help strings are wrapped to fit the width of the terminal, if it is
+/// known. The flags are not styled here, but only when the help is printed
+/// to a terminal, see [`use_color`].
 pub fn print_flags(
+    w: impl Write,
+    indent_size: usize,
+    width: usize,
+    options: impl IntoIterator<Item = (&'static str, &'static str)>,
+) {
+    write_flags(w, indent_size, width, terminal_width(), options);
+}
+
+/// Print a formatted list of subcommands.
+///
+/// This is like [`print_flags`], but with the names of the subcommands
+/// instead of the flags.
+pub fn print_subcommands(
+    w: impl Write,
+    indent_size: usize,
+    width: usize,
+    subcommands: impl IntoIterator<Item = (&'static str, &'static str)>,
+) {
+    write_list(
+        w,
+        "Subcommands",
+        indent_size,
+        width,
+        terminal_width(),
+        subcommands,
+    );
+}
+
+/// The minimum width for help strings below which they are not wrapped
+const MIN_WRAP_WIDTH: usize = 20;
+
+fn write_flags(
+    w: impl Write,
+    indent_size: usize,
+    width: usize,
+    max_width: Option<usize>,
+    options: impl IntoIterator<Item = (&'static str, &'static str)>,
+) {
+    write_list(w, "Options", indent_size, width, max_width, options);
+}
+
+/// Write a list of names with their help strings under a heading
+fn write_list(
     mut w: impl Write,
+    heading: &str,
     indent_size: usize,
     width: usize,
+    max_width: Option<usize>,
     options: impl IntoIterator<Item = (&'static str, &'static str)>,
 ) {
     let indent = " ".repeat(indent_size);
-    writeln!(w, "\nOptions:").unwrap();
+    let wrap_width = max_width
+        .map(|m| m.saturating_sub(width + indent_size + 2))
+        .filter(|&m| m >= MIN_WRAP_WIDTH);
+
+    writeln!(w, "\n{heading}:").unwrap();
     for (flags, help_string) in options {
-        let mut help_lines = help_string.lines();
+        let mut help_lines = help_string
+            .lines()
+            .flat_map(|line| wrap_line(line, wrap_width));
         write!(w, "{}{}", &indent, &flags).unwrap();
 
         if flags.len() <= width {
@@ -146,16 +624,271 @@ pub fn print_flags(
     }
 }
 
+const BOLD: &str = "\x1b[1m";
+const DIM: &str = "\x1b[2m";
+const RESET: &str = "\x1b[0m";
+
+/// Style the flags in the options section of a help string
+///
+/// This relies on the layout of [`print_flags`] as used by the derive
+/// macro: each option starts on a line indented by two spaces and its flags
+/// are followed by at least two spaces or the end of the line. The lines of
+/// a help string that is wrapped are indented further.
+#[cfg_attr(not(feature = "std-io"), allow(dead_code))]
+pub(crate) fn style_help(help: &str) -> String {
+    let mut styled = String::with_capacity(help.len());
+    let mut in_options = false;
+    for line in help.split_inclusive('\n') {
+        let content = line.trim_end_matches('\n');
+        if content == "Options:" {
+            in_options = true;
+        } else if in_options && content.starts_with("   ") {
+            // A wrapped help string
+        } else if in_options && content.starts_with("  ") {
+            let end = content[2..].find("  ").map_or(content.len(), |i| i + 2);
+            styled.push_str(&style_flags(&line[..end]));
+            styled.push_str(&line[end..]);
+            continue;
+        } else {
+            in_options = false;
+        }
+        styled.push_str(line);
+    }
+    styled
+}
+
+/// Make the names in a list of flags bold and their values dim
+///
+/// For example, in `-w WIDTH, --width=WIDTH`, the names are `-w` and
+/// `--width`.
+fn style_flags(flags: &str) -> String {
+    let trimmed = flags.trim_start();
+    let indent = &flags[..flags.len() - trimmed.len()];
+    let styled = trimmed
+        .split(", ")
+        .map(|flag| {
+            let (name, value) = flag.split_at(flag.find([' ', '=', '[']).unwrap_or(flag.len()));
+            if value.is_empty() {
+                format!("{BOLD}{name}{RESET}")
+            } else {
+                format!("{BOLD}{name}{RESET}{DIM}{value}{RESET}")
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!("{indent}{styled}")
+}
+
+/// Split a line at spaces such that each part is at most `width` long
+///
+/// Words longer than `width` are not split.
+fn wrap_line(line: &str, width: Option<usize>) -> Vec<&str> {
+    let Some(width) = width else {
+        return vec![line];
+    };
+
+    let mut lines = Vec::new();
+    let mut rest = line;
+    while rest.chars().count() > width {
+        let split = rest
+            .char_indices()
+            .take(width + 1)
+            .filter(|(_, c)| *c == ' ')
+            .map(|(i, _)| i)
+            .last()
+            .or_else(|| rest.find(' '));
+        let Some(split) = split else {
+            break;
+        };
+        lines.push(rest[..split].trim_end());
+        rest = rest[split..].trim_start();
+    }
+    lines.push(rest);
+    lines
+}
+
+/// Build the arguments for completions from the table of flags and the hints
+/// for their values, which are in the same order.
+#[cfg(feature = "complete")]
+pub fn complete_args(
+    flags: &'static [crate::flags::FlagInfo],
+    hints: Vec<Option<uutils_args_complete::ValueHint>>,
+) -> Vec<uutils_args_complete::Arg<'static>> {
+    flags
+        .iter()
+        .zip(hints)
+        .map(|(info, hint)| info.to_complete(hint))
+        .collect()
+}
+
+/// The hint for the elements of a collection, like the `Vec` of a greedy
+/// option
+#[cfg(feature = "complete")]
+pub fn element_value_hint<C>() -> uutils_args_complete::ValueHint
+where
+    C: IntoIterator,
+    C::Item: Value,
+{
+    C::Item::value_hint()
+}
+
 #[cfg(test)]
 mod test {
     use std::ffi::OsStr;
 
-    use super::is_echo_style_positional;
+    use super::{
+        filter_suggestions, first_deprecated_use, infer_long_option, is_echo_style_positional,
+        style_flags, style_help, wrap_line, write_deprecation_warning, write_flags,
+    };
+    use crate::ErrorKind;
 
     #[test]
     fn echo_positional() {
         assert!(is_echo_style_positional(OsStr::new("-aaa"), &['b']));
         assert!(is_echo_style_positional(OsStr::new("--"), &['b']));
         assert!(!is_echo_style_positional(OsStr::new("-b"), &['b']));
+        assert!(is_echo_style_positional(OsStr::new("-"), &['b']));
+    }
+
+    #[test]
+    fn suggestions_sorted_and_capped() {
+        let options = ["colour", "color", "colors", "column", "colorize", "size"];
+        assert_eq!(
+            filter_suggestions("colr", &options, "--", 0.7),
+            ["--color", "--colour", "--colors"]
+        );
+        assert_eq!(filter_suggestions("colr", &options, "--", 0.99), [""; 0]);
+        assert_eq!(filter_suggestions("size", &options, "", 0.9), ["size"]);
+    }
+
+    #[test]
+    fn ambiguous_candidates_sorted_and_deduplicated() {
+        let options = ["verbose", "version", "verbose", "very", "quiet"];
+        let Err(ErrorKind::AmbiguousOption { candidates, .. }) =
+            infer_long_option("ver", &options, false, 0.7)
+        else {
+            panic!("expected an ambiguous option");
+        };
+        assert_eq!(candidates, ["verbose", "version", "very"]);
+
+        // A duplicate does not make a prefix ambiguous
+        assert_eq!(
+            infer_long_option("verb", &options, false, 0.7).unwrap(),
+            "verbose"
+        );
+
+        let options = ["Color", "color", "colour"];
+        let Err(ErrorKind::AmbiguousOption { candidates, .. }) =
+            infer_long_option("COL", &options, true, 0.7)
+        else {
+            panic!("expected an ambiguous option");
+        };
+        assert_eq!(candidates, ["Color", "color", "colour"]);
+    }
+
+    #[test]
+    fn deprecated_warns_once() {
+        assert!(first_deprecated_use("--some-old-option"));
+        assert!(!first_deprecated_use("--some-old-option"));
+        assert!(first_deprecated_use("--other-old-option"));
+    }
+
+    #[test]
+    fn deprecation_warning_output() {
+        let mut out = Vec::new();
+        for _ in 0..2 {
+            write_deprecation_warning(&mut out, "ls", "--old", "use --new instead").unwrap();
+        }
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "ls: warning: option '--old' is deprecated: use --new instead\n"
+        );
+    }
+
+    #[test]
+    fn wrap() {
+        let line = "one two three four";
+        assert_eq!(wrap_line(line, None), [line]);
+        assert_eq!(wrap_line(line, Some(100)), [line]);
+        assert_eq!(wrap_line(line, Some(9)), ["one two", "three", "four"]);
+        assert_eq!(wrap_line(line, Some(13)), ["one two three", "four"]);
+        assert_eq!(wrap_line("abcdefgh ij", Some(4)), ["abcdefgh", "ij"]);
+        assert_eq!(wrap_line("", Some(4)), [""]);
+    }
+
+    #[test]
+    fn wrapped_flags() {
+        let help = "Some very long help text that does not fit on a single line";
+        let options = [("-a, --all", help)];
+
+        let mut unwrapped = String::new();
+        write_flags(&mut unwrapped, 2, 16, None, options);
+        assert_eq!(
+            unwrapped,
+            format!("\nOptions:\n  -a, --all         {help}\n")
+        );
+
+        let mut wrapped = String::new();
+        write_flags(&mut wrapped, 2, 16, Some(60), options);
+        assert_eq!(
+            wrapped,
+            "\nOptions:
+  -a, --all         Some very long help text that does not
+                    fit on a single line
+"
+        );
+
+        // Don't wrap if there is too little space
+        let mut narrow = String::new();
+        write_flags(&mut narrow, 2, 16, Some(30), options);
+        assert_eq!(narrow, unwrapped);
+    }
+
+    #[test]
+    fn styled_flags() {
+        assert_eq!(
+            style_flags("-a, --all"),
+            "\x1b[1m-a\x1b[0m, \x1b[1m--all\x1b[0m"
+        );
+        assert_eq!(
+            style_flags("    --color[=WHEN]"),
+            "    \x1b[1m--color\x1b[0m\x1b[2m[=WHEN]\x1b[0m"
+        );
+    }
+
+    #[test]
+    fn styled_help() {
+        let options = [
+            ("-w WIDTH", "The width"),
+            ("--all", "Some very long help text that does not fit"),
+            ("--very-long-flag", ""),
+        ];
+        let mut plain = String::from("test 1.0\n\nUsage:\n  test [OPTIONS]\n");
+        write_flags(&mut plain, 2, 10, Some(40), options);
+        plain.push_str("\n  Indented after help\n");
+        assert_eq!(
+            plain,
+            "test 1.0\n\nUsage:\n  test [OPTIONS]\n\nOptions:
+  -w WIDTH    The width
+  --all       Some very long help text
+              that does not fit
+  --very-long-flag
+
+  Indented after help
+"
+        );
+
+        // Only the flags are styled, so the alignment does not change
+        assert_eq!(
+            style_help(&plain),
+            "test 1.0\n\nUsage:\n  test [OPTIONS]\n\nOptions:
+  \x1b[1m-w\x1b[0m\x1b[2m WIDTH\x1b[0m    The width
+  \x1b[1m--all\x1b[0m       Some very long help text
+              that does not fit
+  \x1b[1m--very-long-flag\x1b[0m
+
+  Indented after help
+"
+        );
     }
 }
@@ -10,8 +10,10 @@
 #![doc = include_str!("../README.md")]
 
 mod error;
+pub mod flags;
 pub mod internal;
 pub mod positional;
+mod response_files;
 mod value;
 
 #[cfg(doc)]
@@ -50,13 +52,369 @@ pub use uutils_args_derive::Value;
 /// | `--long=VAL`   | long       | required |
 /// | `--long[=VAL]` | long       | optional |
 /// | `long=VAL`     | dd         | required |
+/// | `+VAL`         | plus       | required |
 ///
+/// A `+VAL` argument captures any argument starting with `+`, like the
+/// `+FORMAT` argument of `date`, if the rest of the argument can be parsed
+/// as the value of the variant, using its `parser` and `range` if it has
+/// them. Otherwise, the next `+VAL` argument is
+/// tried, in the order of the variants, and if none of them match, the
+/// argument is a positional argument. This makes it possible to
+/// distinguish `+5` from `+%s` by the type of the value.
+///
+/// Short flags can be combined in a cluster, like `-ab` for `-a -b`. The
+/// first flag in a cluster that takes a value, required or optional, takes
+/// the rest of the cluster as its value. For example, if `-I[FMT]` takes an
+/// optional value, `-aIminutes` is `-a -Iminutes` and if `-n N` takes a
+/// required value, `-anb` is `-a -n b`. A required value at the end of a
+/// cluster, like in `-an 5`, is taken from the next argument.
+///
+/// An optional value of a long option must be given with an equals sign.
+/// Without it, as in `--color`, the `value = ...` of the argument is used,
+/// or [`Default::default`] if there is none. With an equals sign but no
+/// value, as in `--color=`, the empty string is parsed with
+/// [`Value::from_value`], which decides whether it is valid.
+///
+/// Every flag can only be defined once. Defining a flag for multiple
+/// variants results in a compile error:
+///
+/// ```compile_fail
+/// use uutils_args::Arguments;
+///
+/// #[derive(Arguments)]
+/// enum Arg {
+///     #[arg("-c", "--count")]
+///     Count,
+///     #[arg("-c", "--color")]
+///     Color,
+/// }
+/// ```
+///
+/// Older versions used `#[option(...)]` instead of `#[arg(...)]` and
+/// `default = ...` instead of `value = ...`. Both are rejected with an
+/// error pointing to the new name:
+///
+/// ```compile_fail
+/// use uutils_args::Arguments;
+///
+/// #[derive(Arguments)]
+/// enum Arg {
+///     #[option("-c", "--count")]
+///     Count,
+/// }
+/// ```
+///
+/// An option can take multiple values with `nargs`, e.g.
+/// `#[arg("--size W H", nargs = 2)]`. The values are taken from the
+/// following arguments and the field of the variant must be an array or a
+/// tuple with that many elements. If fewer values are given, parsing fails
+/// with [`ErrorKind::MissingValue`]. An option that takes no values is a
+/// flag without a value, so `nargs = 0` is rejected at compile time:
+///
+/// ```compile_fail
+/// use uutils_args::Arguments;
+///
+/// #[derive(Arguments)]
+/// enum Arg {
+///     #[arg("--size W H", nargs = 0)]
+///     Size([u32; 0]),
+/// }
+/// ```
+///
+/// With `greedy`, as in `#[arg("--exec=CMD", greedy)]`, an option takes all
+/// following arguments as values, including ones that look like options,
+/// until a `--` or the end of the arguments. The arguments after the `--` are
+/// operands. The field must be a `Vec` and at least one value is required.
+///
+/// An option marked with `terminator`, as in `#[arg("--end", terminator)]`,
+/// ends the options like `--` does: all arguments after it are operands,
+/// including `--` itself. The option is still applied as usual.
+///
+/// The values of an option can be restricted to a range with `range`, e.g.
+/// `#[arg("--count=N", range = 1..=100)]`. A value outside of the range
+/// results in [`ErrorKind::ParsingFailed`].
+///
+/// The message for a value that fails to parse can be replaced with
+/// `error`, e.g. `#[arg("--mode=MODE", error = "invalid mode: '{value}'")]`.
+/// The placeholders `{value}`, `{option}` and `{error}` are filled in with
+/// the value, the option and the original error. The error is still
+/// [`ErrorKind::ParsingFailed`]. Like in `format!`, `{{` and `}}` are
+/// literal braces. Other placeholders are rejected at compile time:
+///
+/// ```compile_fail
+/// use uutils_args::Arguments;
+///
+/// #[derive(Arguments)]
+/// enum Arg {
+///     #[arg("--mode=MODE", error = "invalid mode: {val}")]
+///     Mode(u32),
+/// }
+/// ```
+///
+/// Old spellings of a flag can be given with `alias`, e.g.
+/// `#[arg("-w N", "--width=N", alias = "--cols=N")]`. An alias is parsed like
+/// the other flags, but it is not shown in the help or the completions.
+///
+/// An option marked as `hidden` is left out of the help and the completions.
+/// To hide it from only one of them, use `hidden_help` or `hidden_complete`
+/// instead, e.g. `#[arg("--debug", hidden_help)]` for an option that can
+/// still be completed.
+///
+/// Instead of implementing [`Value`] for the type of the field, a function
+/// can be given to parse the value with `parser`, e.g.
+/// `#[arg("--tabsize=N", parser = parse_tabsize)]`. The function takes the
+/// value as an [`OsStr`](std::ffi::OsStr) and returns a [`ValueResult`]. An
+/// error is reported as [`ErrorKind::ParsingFailed`], just like an error from
+/// [`Value`].
+///
+/// With `no_dash_value`, as in `#[arg("-t DIR", "--target=DIR", no_dash_value)]`,
+/// a required value is not taken from a separate argument starting with a
+/// hyphen, such as the `-r` in `--target -r`. Instead, parsing fails with
+/// [`ErrorKind::MissingValue`]. A value given explicitly, as in `--target=-r`
+/// or `-t-r`, and a single hyphen are still accepted.
+///
+/// ## Operands
+///
+/// By default, operands are returned by [`Options::parse`]. Alternatively,
+/// a variant marked with `#[arg(operands)]` receives all operands at once
+/// in a `Vec`, after all options have been applied. Each operand is parsed
+/// with [`Value`] and the operands are then no longer returned separately.
+///
+/// The operands can be described with
+/// `#[arguments(usage_operands = ["SOURCE...", "DEST"])]`, in the same form
+/// as in a usage line. They are shown in the usage of the help, as in
+/// `cp [OPTIONS] SOURCE... DEST`, unless the help file has its own usage,
+/// and in the generated documentation. This is only a description: the
+/// operands are still unpacked with [`positional`].
+///
+/// The first `--` only separates the options from the operands, so it is not
+/// an operand itself. Any `--` after it is an operand: `basename -a -- --foo --`
+/// has the operands `--foo` and `--`. Echo-style parsing is the exception,
+/// because `echo` does not treat `--` specially.
+///
+/// ```
+/// use std::path::PathBuf;
+/// use uutils_args::{Arguments, Options};
+///
+/// #[derive(Arguments)]
+/// enum Arg {
+///     #[arg("-n")]
+///     Number,
+///     #[arg(operands)]
+///     Files(Vec<PathBuf>),
+/// }
+///
+/// #[derive(Default)]
+/// struct Settings {
+///     number: bool,
+///     files: Vec<PathBuf>,
+/// }
+///
+/// impl Options<Arg> for Settings {
+///     fn apply(&mut self, arg: Arg) {
+///         match arg {
+///             Arg::Number => self.number = true,
+///             Arg::Files(files) => self.files = files,
+///         }
+///     }
+/// }
+///
+/// let (settings, operands) = Settings::default().parse(["cat", "a", "-n", "b"]).unwrap();
+/// assert!(settings.number);
+/// assert_eq!(settings.files, [PathBuf::from("a"), PathBuf::from("b")]);
+/// assert!(operands.is_empty());
+/// ```
+///
+/// ## Subcommands
+///
+/// A variant marked with `#[arg(subcommand)]` must hold a single field of
+/// another type implementing [`Arguments`]. If the first operand is the
+/// lowercased name of the variant (or the name given with
+/// `#[arg(subcommand = "name")]`), all following arguments are parsed by
+/// that type and passed to [`Options::apply`] wrapped in the variant. The
+/// name of the subcommand is kept as the first operand.
+///
+/// The help of the parent command lists the subcommands with the doc
+/// comments of their variants. Once a subcommand is active, `--help` prints
+/// the help of the subcommand instead.
+///
+/// ```
+/// use uutils_args::{Arguments, Options};
+///
+/// #[derive(Arguments)]
+/// enum AddArg {
+///     #[arg("-f", "--force")]
+///     Force,
+/// }
+///
+/// #[derive(Arguments)]
+/// enum Arg {
+///     #[arg("-v", "--verbose")]
+///     Verbose,
+///     #[arg(subcommand)]
+///     Add(AddArg),
+/// }
+///
+/// #[derive(Default)]
+/// struct Settings {
+///     verbose: bool,
+///     force: bool,
+/// }
+///
+/// impl Options<Arg> for Settings {
+///     fn apply(&mut self, arg: Arg) {
+///         match arg {
+///             Arg::Verbose => self.verbose = true,
+///             Arg::Add(AddArg::Force) => self.force = true,
+///         }
+///     }
+/// }
+///
+/// let (settings, operands) = Settings::default()
+///     .parse(["git", "-v", "add", "-f", "file"])
+///     .unwrap();
+/// assert!(settings.verbose);
+/// assert!(settings.force);
+/// assert_eq!(operands, ["add", "file"]);
+/// ```
+///
+/// ## Exit codes
+///
+/// Errors exit with code 1 by default. This can be changed with
+/// `#[arguments(exit_code = 2)]`. Usage errors, like an unknown option, and
+/// other errors, like I/O errors, can also be given different codes with
+/// `#[arguments(usage_exit_code = 2, error_exit_code = 1)]`. See
+/// [`ErrorKind::is_usage_error`].
+///
+/// ## Required groups
+///
+/// Some commands need exactly one option out of a group, like `cut`, which
+/// needs one of `-b`, `-c` and `-f`. Such a group is declared with the names
+/// of its variants, as in
+/// `#[arguments(required_group = ["Bytes", "Characters", "Fields"])]`, and the
+/// attribute can be repeated for multiple groups. If none of the options is
+/// given, parsing fails with [`ErrorKind::MissingRequiredGroup`]. If more
+/// than one is given, it fails with [`ErrorKind::TooManyInGroup`]. Repeating
+/// the same option is fine. The errors show the first long flag of each
+/// option, or the first short flag if it has no long flags.
+///
+/// ## Echo-style parsing
+///
+/// With `#[arguments(parse_echo_style)]`, the arguments are parsed like
+/// `echo` does. An argument is only an option if it consists of a hyphen
+/// followed by known short flags, like `-n` or `-ne`. Every other argument is
+/// an operand, including `-`, `--` and arguments with an unknown flag, such as
+/// `-nx`. This differs from `options_first` in which arguments end the
+/// options: there, `--` is still a separator and unknown flags are errors.
+/// The first operand and all arguments after it are returned as operands.
+///
+/// ## Unknown options
+///
+/// By default, an unknown option is an error. Wrappers that pass options
+/// they do not know on to another command can use
+/// `#[arguments(passthrough_unknown)]` instead. Unknown options are then
+/// returned by [`Options::parse`] together with the operands, in the order in
+/// which they were given, so that both can be forwarded as they are. An
+/// [`ArgIterator`] yields them as [`Argument::Unknown`]. Since it is not known
+/// whether an unknown option takes a value, a separate value is returned as an
+/// operand, while a value attached with `=` stays part of the option.
+///
+/// A `--` is kept in the returned arguments if any of the arguments after it
+/// starts with a hyphen, so that the command they are forwarded to does not
+/// mistake them for options.
+///
+/// ## Response files
+///
+/// With `#[arguments(response_files)]`, an argument like `@args.txt` is
+/// replaced by the contents of `args.txt` before parsing, with one argument
+/// per line. A response file can contain other `@file` arguments, up to 10
+/// levels deep. Arguments after `--` are not expanded and neither is a lone
+/// `@`. If a file cannot be read, parsing fails with
+/// [`ErrorKind::ResponseFile`], which is not a usage error. The indices of
+/// [`Options::parse_indexed`] refer to the expanded arguments. An
+/// [`ArgIterator`] does not expand response files.
+///
+/// The arguments are expanded before they are parsed, so it is not known
+/// which of them are values of options. In `--name @file`, the value is
+/// read from `file` too, and in `--name -- @file`, the `--` is the value of
+/// `--name` but still stops the expansion of `@file`. On platforms other
+/// than Unix, the lines may end with `\r\n`.
+///
+/// ## Help short-circuiting
+///
+/// By default, arguments are processed in order, so an invalid option
+/// before `--help` results in an error. With
+/// `#[arguments(help_short_circuits)]`, the remaining arguments are
+/// searched for a help or version flag when parsing an argument fails. If
+/// one is found, it takes precedence over the error. Only flags given as
+/// separate arguments before any `--` are considered.
+///
+/// ## Help files
+///
+/// The summary, usage and extra help sections can be read from a markdown
+/// file with `#[arguments(file = "help.md")]`. By default, the path is
+/// resolved relative to the directory containing the `Cargo.toml` of the
+/// crate. With `#[arguments(file = "help.md", file_base = "source")]`, it is
+/// resolved relative to the directory of the source file containing the
+/// derive instead. The file is embedded with `include_str!`, so changes to
+/// it cause the crate to be rebuilt. If the file has no usage code block,
+/// the help shows the default usage, like without a help file: the
+/// operands from `usage_operands` or `[ARGUMENTS]`.
+///
+/// The help of an option can also come from a section of the help file, so
+/// that all help text can be kept in one place, for example for translators.
+/// With `#[arg("--verbose", help_section = "verbose")]`, the text under the
+/// `## verbose` heading is used instead of the doc comment, both in the help
+/// and in the completions.
+///
+/// After the options, the help shows the `## Examples` and `## Environment`
+/// sections of the help file, in that order, under the headings `Examples:`
+/// and `Environment:`. The `## After help` section comes last. The lines of
+/// code fences are left out, so commands in code blocks are shown as is.
+///
+/// ## Version
+///
+/// The version string consists of the name of the binary and the version
+/// of the crate. Extra text between them can be added with
+/// `#[arguments(version_extra = "(GNU coreutils)")]`, which gives
+/// `ls (GNU coreutils) 1.0.0`. See also [`set_version_hook`].
 pub use uutils_args_derive::Arguments;
 
-pub use error::{Error, ErrorKind};
-pub use value::{Value, ValueError, ValueResult};
+pub use error::{Error, ErrorCode, ErrorKind};
+pub use value::{
+    BlockSize, Bytes, FromStrValue, KeyValue, NonEmptyPath, OsStringList, Size, SizeError, Value,
+    ValueError, ValueResult,
+};
+
+use std::{ffi::OsString, io::Write};
 
-use std::{ffi::OsString, marker::PhantomData};
+/// Enable or disable the warnings for deprecated options.
+///
+/// Options can be marked as deprecated with
+/// `#[arg("--old", deprecated = "use --new instead")]`. When such an option
+/// is used, a warning is printed to stderr the first time it is encountered.
+/// This is tracked for the whole process, so an option is only warned about
+/// once even if the arguments are parsed multiple times. These warnings are
+/// enabled by default. To support a flag like `--no-warnings`, this function
+/// must be called before parsing.
+pub fn set_deprecation_warnings(enabled: bool) {
+    internal::DEPRECATION_WARNINGS.store(enabled, std::sync::atomic::Ordering::Relaxed);
+}
+
+/// Set a function to transform the version string before it is printed.
+///
+/// When `--version` is passed to [`Options::parse`] or [`Arguments::check`],
+/// the version string is passed through `hook` before it is printed. This
+/// allows a multicall binary to format the version of all its utilities in
+/// the same way. For full control over what happens on `--version`, use
+/// [`ArgIterator`], which yields [`Argument::Version`] instead of printing.
+///
+/// ```
+/// uutils_args::set_version_hook(|version| format!("{version}\nCopyright (C) 2024"));
+/// ```
+pub fn set_version_hook(hook: fn(String) -> String) {
+    *internal::VERSION_HOOK.lock().unwrap() = Some(hook);
+}
 
 /// A wrapper around a type implementing [`Arguments`] that adds `Help`
 /// and `Version` variants.
@@ -67,91 +425,538 @@ pub enum Argument<T: Arguments> {
     Positional(OsString),
     MultiPositional(Vec<OsString>),
     Custom(T),
+    /// An option that is not known, with `#[arguments(passthrough_unknown)]`
+    ///
+    /// A long option contains its value if it was given with `=`, like
+    /// `--foo=bar`. Each unknown flag in a cluster of short options is a
+    /// separate argument, like `-x`.
+    Unknown(OsString),
+    /// A subcommand with the given name was found.
+    ///
+    /// All following arguments should be parsed with `next_arg` and the help
+    /// for the subcommand is given by `help`.
+    Subcommand {
+        name: OsString,
+        next_arg: NextArg<T>,
+        help: fn(&str) -> String,
+    },
 }
 
+/// The signature of [`Arguments::next_arg`].
+pub type NextArg<T> = fn(&mut lexopt::Parser) -> Result<Option<Argument<T>>, ErrorKind>;
+
 /// Defines how the arguments are parsed.
 ///
 /// Usually, this trait will be implemented via the
 /// [derive macro](derive@Arguments) and does not need to be implemented
 /// manually.
 pub trait Arguments: Sized {
-    /// The exit code to exit the program with on error.
+    /// The exit code to exit the program with on a usage error.
+    ///
+    /// See [`ErrorKind::is_usage_error`].
     const EXIT_CODE: i32;
 
+    /// The exit code to exit the program with on an error that is not a
+    /// usage error, such as an I/O error.
+    const ERROR_EXIT_CODE: i32 = Self::EXIT_CODE;
+
+    /// Whether `@file` arguments are replaced by the lines of `file`
+    ///
+    /// See the section on response files above.
+    const RESPONSE_FILES: bool = false;
+
+    /// Whether unknown options are returned instead of failing
+    ///
+    /// See the section on unknown options above.
+    const PASSTHROUGH_UNKNOWN: bool = false;
+
+    /// Groups of options of which exactly one must be given
+    ///
+    /// Each option is the name of its variant together with the flag that
+    /// is shown for it in errors. See the section on required groups above.
+    const REQUIRED_GROUPS: &'static [&'static [(&'static str, &'static str)]] = &[];
+
+    /// The name of the variant of this argument, for [`Arguments::REQUIRED_GROUPS`]
+    fn variant_name(&self) -> &'static str {
+        ""
+    }
+
     /// Parse the next argument from the lexopt parser.
     fn next_arg(parser: &mut lexopt::Parser) -> Result<Option<Argument<Self>>, ErrorKind>;
 
+    /// Collect all operands into a single argument.
+    ///
+    /// This is generated by the derive macro for a variant with
+    /// `#[arg(operands)]`. If it returns an argument, it is applied after all
+    /// options and the operands are no longer returned by [`Options::parse`].
+    fn operands(_operands: &[OsString]) -> Result<Option<Self>, ErrorKind> {
+        Ok(None)
+    }
+
     /// Print the help string for this command.
     ///
     /// The `bin_name` specifies the name that executable was called with.
     fn help(bin_name: &str) -> String;
 
+    /// Print the help string for this command with the given program name.
+    ///
+    /// This is like [`Arguments::help`], but shows `name` instead of the
+    /// name of the cargo binary in the header. This is useful for multicall
+    /// binaries, where each applet should show its own name.
+    fn help_with_name(name: &str, bin_name: &str) -> String;
+
     /// Get the version string for this command.
     fn version() -> String;
 
+    /// Get the version string for this command with the given program name.
+    ///
+    /// See [`Arguments::help_with_name`].
+    fn version_with_name(name: &str) -> String;
+
     /// Check all arguments immediately and return any errors.
     ///
-    /// This is useful if you want to validate the arguments. This method will
-    /// exit if `--help` or `--version` are passed.
+    /// This is useful if you want to validate the arguments. If `--help` or
+    /// `--version` are passed, [`ErrorKind::HelpRequested`] or
+    /// [`ErrorKind::VersionRequested`] is returned.
     fn check<I>(args: I) -> Result<(), Error>
     where
         I: IntoIterator,
         I::Item: Into<OsString>,
     {
-        let mut iter = ArgumentIter::<Self>::from_args(args);
-        while iter.next_arg()?.is_some() {}
+        let mut iter = ArgIterator::<Self>::from_args(expand_args::<Self, _>(args, true)?);
+        let mut operands = Vec::new();
+        let mut seen = Vec::new();
+        while let Some(arg) = iter.next_arg()? {
+            help_or_version_error(&iter, &arg)?;
+            match arg {
+                Argument::Positional(arg)
+                | Argument::Subcommand { name: arg, .. }
+                | Argument::Unknown(arg) => operands.push(arg),
+                Argument::MultiPositional(args) => operands.extend(args),
+                Argument::Custom(arg) => seen.push(arg.variant_name()),
+                _ => {}
+            }
+        }
+        check_required_groups::<Self>(&seen).map_err(Error::from_kind::<Self>)?;
+        Self::operands(&operands).map_err(Error::from_kind::<Self>)?;
         Ok(())
     }
 
+    /// Check all arguments and return all errors.
+    ///
+    /// Unlike [`Arguments::check`], this continues after recoverable errors
+    /// (see [`ErrorKind::is_recoverable`]), so that all problems can be
+    /// reported at once. It stops at the first fatal error. A request for the
+    /// help or version is returned as a fatal error, like in
+    /// [`Arguments::check`].
+    fn check_all<I>(args: I) -> Vec<Error>
+    where
+        I: IntoIterator,
+        I::Item: Into<OsString>,
+    {
+        let args = match expand_args::<Self, _>(args, true) {
+            Ok(args) => args,
+            Err(err) => return vec![err],
+        };
+        let mut iter = ArgIterator::<Self>::from_args(args);
+        let mut errors = Vec::new();
+        let mut operands = Vec::new();
+        let mut seen = Vec::new();
+        loop {
+            match iter.next_arg() {
+                Ok(Some(arg)) => {
+                    if let Err(err) = help_or_version_error(&iter, &arg) {
+                        errors.push(err);
+                        break;
+                    }
+                    match arg {
+                        Argument::Positional(arg)
+                        | Argument::Subcommand { name: arg, .. }
+                        | Argument::Unknown(arg) => operands.push(arg),
+                        Argument::MultiPositional(args) => operands.extend(args),
+                        Argument::Custom(arg) => seen.push(arg.variant_name()),
+                        _ => {}
+                    }
+                }
+                Ok(None) => {
+                    if let Err(kind) = check_required_groups::<Self>(&seen) {
+                        errors.push(Error::from_kind::<Self>(kind));
+                    }
+                    if let Err(kind) = Self::operands(&operands) {
+                        errors.push(Error::from_kind::<Self>(kind));
+                    }
+                    break;
+                }
+                Err(err) => {
+                    let recoverable = err.kind.is_recoverable();
+                    errors.push(err);
+                    if !recoverable {
+                        break;
+                    }
+                }
+            }
+        }
+        errors
+    }
+
+    /// All options with short or long flags, in the order they are defined
+    ///
+    /// This is available without the `complete` feature and can be used to
+    /// list the flags at runtime.
+    fn flags() -> &'static [flags::FlagInfo];
+
     #[cfg(feature = "complete")]
     fn complete() -> uutils_args_complete::Command<'static>;
 }
 
-/// An iterator over arguments.
-struct ArgumentIter<T: Arguments> {
+/// Check that exactly one option of each of [`Arguments::REQUIRED_GROUPS`]
+/// was given, where `seen` are the variant names of the parsed options
+fn check_required_groups<T: Arguments>(seen: &[&str]) -> Result<(), ErrorKind> {
+    for group in T::REQUIRED_GROUPS {
+        let given: Vec<String> = group
+            .iter()
+            .filter(|(variant, _)| seen.contains(variant))
+            .map(|(_, flag)| flag.to_string())
+            .collect();
+        match given.len() {
+            0 => {
+                return Err(ErrorKind::MissingRequiredGroup(
+                    group.iter().map(|(_, flag)| flag.to_string()).collect(),
+                ))
+            }
+            1 => {}
+            _ => return Err(ErrorKind::TooManyInGroup(given)),
+        }
+    }
+    Ok(())
+}
+
+/// Collect the arguments, replacing response files if `T` accepts them
+fn expand_args<T: Arguments, I>(args: I, has_bin_name: bool) -> Result<Vec<OsString>, Error>
+where
+    I: IntoIterator,
+    I::Item: Into<OsString>,
+{
+    let args = args.into_iter().map(Into::into).collect();
+    if !T::RESPONSE_FILES {
+        return Ok(args);
+    }
+    response_files::expand(args, has_bin_name).map_err(Error::from_kind::<T>)
+}
+
+/// Turn a request for the help or version into an error with exit code 0
+fn help_or_version_error<T: Arguments>(
+    iter: &ArgIterator<T>,
+    arg: &Argument<T>,
+) -> Result<(), Error> {
+    let kind = match arg {
+        Argument::Help => ErrorKind::HelpRequested(iter.help()),
+        Argument::Version => {
+            let version = T::version();
+            let version = match *internal::VERSION_HOOK.lock().unwrap() {
+                Some(hook) => hook(version),
+                None => version,
+            };
+            ErrorKind::VersionRequested(version)
+        }
+        _ => return Ok(()),
+    };
+    Err(Error { exit_code: 0, kind })
+}
+
+/// An iterator over the parsed arguments
+///
+/// This gives full control over the parsing, without the need to implement
+/// [`Options`]. Unlike [`Options::parse`], it does not print anything or
+/// exit on `--help` and `--version`, but yields [`Argument::Help`] and
+/// [`Argument::Version`] instead. Operands are yielded as
+/// [`Argument::Positional`] or [`Argument::MultiPositional`].
+///
+/// If the first operand is a subcommand, [`Argument::Subcommand`] is
+/// yielded and all following arguments are parsed by the subcommand.
+///
+/// After an error, the iterator skips the argument that caused it, so
+/// parsing can continue with the next argument.
+///
+/// ```
+/// use uutils_args::{ArgIterator, Argument, Arguments};
+///
+/// #[derive(Arguments)]
+/// enum Arg {
+///     #[arg("-v", "--verbose")]
+///     Verbose,
+/// }
+///
+/// let mut verbose = false;
+/// let mut operands = Vec::new();
+/// for arg in ArgIterator::<Arg>::from_args(["test", "-v", "foo", "--help"]) {
+///     match arg.unwrap() {
+///         Argument::Custom(Arg::Verbose) => verbose = true,
+///         Argument::Positional(operand) => operands.push(operand),
+///         Argument::Help => println!("{}", Arg::help("test")),
+///         _ => {}
+///     }
+/// }
+/// assert!(verbose);
+/// assert_eq!(operands, ["foo"]);
+/// ```
+pub struct ArgIterator<T: Arguments> {
     parser: lexopt::Parser,
-    positional_arguments: Vec<OsString>,
-    t: PhantomData<T>,
+    /// Whether an operand has been found, after which subcommands are no
+    /// longer recognized
+    found_operand: bool,
+    subcommand: Option<ActiveSubcommand<T>>,
 }
 
-impl<T: Arguments> ArgumentIter<T> {
-    fn from_args<I>(args: I) -> Self
+/// The subcommand that is used to parse the remaining arguments.
+struct ActiveSubcommand<T: Arguments> {
+    name: OsString,
+    next_arg: NextArg<T>,
+    help: fn(&str) -> String,
+}
+
+impl<T: Arguments> ArgIterator<T> {
+    /// Create an iterator from the arguments, where the first argument is
+    /// the binary name
+    pub fn from_args<I>(args: I) -> Self
     where
         I: IntoIterator,
         I::Item: Into<OsString>,
     {
         Self {
-            parser: lexopt::Parser::from_iter(args),
-            positional_arguments: Vec::new(),
-            t: PhantomData,
+            parser: lexopt::Parser::from_iter(args.into_iter().map(Into::into)),
+            found_operand: false,
+            subcommand: None,
         }
     }
 
-    pub fn next_arg(&mut self) -> Result<Option<T>, Error> {
-        while let Some(arg) = T::next_arg(&mut self.parser).map_err(|kind| Error {
-            exit_code: T::EXIT_CODE,
-            kind,
-        })? {
-            match arg {
-                Argument::Help => {
-                    print!("{}", T::help(self.parser.bin_name().unwrap()));
-                    std::process::exit(0);
+    /// Create an iterator from the arguments, without a binary name
+    ///
+    /// All arguments are parsed, including the first. This is useful for
+    /// arguments that do not come from the command line, such as a line in a
+    /// configuration file. [`ArgIterator::bin_name`] returns `None`.
+    pub fn from_args_without_bin_name<I>(args: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<OsString>,
+    {
+        Self {
+            parser: lexopt::Parser::from_args(args.into_iter().map(Into::into)),
+            found_operand: false,
+            subcommand: None,
+        }
+    }
+
+    /// Parse the next argument
+    pub fn next_arg(&mut self) -> Result<Option<Argument<T>>, Error> {
+        let next_arg = match &self.subcommand {
+            Some(subcommand) => subcommand.next_arg,
+            None => T::next_arg,
+        };
+        let remaining = self.remaining_args();
+        let arg = next_arg(&mut self.parser).map_err(|kind| {
+            self.skip_failed_arg(remaining);
+            Error::from_kind::<T>(kind)
+        })?;
+        Ok(match arg {
+            Some(Argument::Subcommand {
+                name,
+                next_arg,
+                help,
+            }) => {
+                // A subcommand is only recognized as the first operand,
+                // otherwise it is just a regular operand.
+                if self.found_operand {
+                    Some(Argument::Positional(name))
+                } else {
+                    self.found_operand = true;
+                    self.subcommand = Some(ActiveSubcommand {
+                        name: name.clone(),
+                        next_arg,
+                        help,
+                    });
+                    Some(Argument::Subcommand {
+                        name,
+                        next_arg,
+                        help,
+                    })
                 }
-                Argument::Version => {
-                    print!("{}", T::version());
-                    std::process::exit(0);
+            }
+            Some(arg @ (Argument::Positional(_) | Argument::MultiPositional(_))) => {
+                self.found_operand = true;
+                Some(arg)
+            }
+            arg => arg,
+        })
+    }
+
+    /// The number of arguments that have not been consumed yet, unless the
+    /// parser is in the middle of an argument
+    fn remaining_args(&mut self) -> Option<usize> {
+        Some(self.parser.try_raw_args()?.as_slice().len())
+    }
+
+    /// Make sure the argument that caused an error is not parsed again
+    fn skip_failed_arg(&mut self, remaining_before: Option<usize>) {
+        // Discard the rest of a partially parsed argument.
+        let _ = self.parser.optional_value();
+
+        // Some errors are returned before the argument is consumed.
+        if remaining_before.is_some() && remaining_before == self.remaining_args() {
+            if let Some(mut raw) = self.parser.try_raw_args() {
+                raw.next();
+            }
+        }
+    }
+
+    /// The name that the binary was called with
+    pub fn bin_name(&self) -> Option<&str> {
+        self.parser.bin_name()
+    }
+
+    /// The help string of the command, or of the active subcommand
+    pub fn help(&self) -> String {
+        let bin_name = self.bin_name().unwrap_or_default();
+        match &self.subcommand {
+            Some(subcommand) => {
+                (subcommand.help)(&format!("{bin_name} {}", subcommand.name.to_string_lossy()))
+            }
+            None => T::help(bin_name),
+        }
+    }
+}
+
+impl<T: Arguments> Iterator for ArgIterator<T> {
+    type Item = Result<Argument<T>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_arg().transpose()
+    }
+}
+
+/// An iterator over arguments that handles help, version and operands.
+#[cfg(not(feature = "parse-is-complete"))]
+struct ArgumentIter<T: Arguments> {
+    args: ArgIterator<T>,
+    /// The total number of arguments, including the binary name
+    num_args: usize,
+    /// The positional arguments and unknown options with their index in the
+    /// arguments
+    positional_arguments: Vec<(usize, OsString)>,
+    /// The index of the argument containing the last parsed option
+    option_index: usize,
+    /// The variant names of the parsed options, for the required groups
+    seen: Vec<&'static str>,
+    /// The index of the `--` that ends the options, if it was found
+    separator: Option<usize>,
+}
+
+#[cfg(not(feature = "parse-is-complete"))]
+impl<T: Arguments> ArgumentIter<T> {
+    fn from_args<I>(args: I, has_bin_name: bool) -> Result<Self, Error>
+    where
+        I: IntoIterator,
+        I::Item: Into<OsString>,
+    {
+        let args = expand_args::<T, _>(args, has_bin_name)?;
+        Ok(Self {
+            num_args: args.len(),
+            args: if has_bin_name {
+                ArgIterator::from_args(args)
+            } else {
+                ArgIterator::from_args_without_bin_name(args)
+            },
+            positional_arguments: Vec::new(),
+            option_index: 0,
+            seen: Vec::new(),
+            separator: None,
+        })
+    }
+
+    /// The next option with the index of the argument it starts in
+    pub fn next_arg(&mut self) -> Result<Option<(usize, T)>, Error> {
+        loop {
+            // If the parser is in the middle of a cluster of short options,
+            // the next option is in the same argument as the previous one.
+            if let Some(raw) = self.args.parser.try_raw_args() {
+                self.option_index = self.num_args - raw.as_slice().len();
+                // Only the first `--` ends the options, later ones are operands.
+                if T::PASSTHROUGH_UNKNOWN
+                    && self.separator.is_none()
+                    && raw.peek() == Some("--".as_ref())
+                {
+                    self.separator = Some(self.option_index);
                 }
-                Argument::Positional(arg) => {
-                    self.positional_arguments.push(arg);
+            }
+            let Some(arg) = self.args.next_arg()? else {
+                break;
+            };
+            help_or_version_error(&self.args, &arg)?;
+            match arg {
+                Argument::Help | Argument::Version => unreachable!(),
+                Argument::Positional(arg) | Argument::Subcommand { name: arg, .. } => {
+                    let index = self.next_index() - 1;
+                    self.positional_arguments.push((index, arg));
                 }
                 Argument::MultiPositional(args) => {
-                    self.positional_arguments.extend(args);
+                    let start = self.next_index() - args.len();
+                    self.positional_arguments.extend((start..).zip(args));
+                }
+                Argument::Unknown(arg) => self.positional_arguments.push((self.option_index, arg)),
+                Argument::Custom(arg) => {
+                    self.seen.push(arg.variant_name());
+                    return Ok(Some((self.option_index, arg)));
                 }
-                Argument::Custom(arg) => return Ok(Some(arg)),
             }
         }
+        check_required_groups::<T>(&self.seen).map_err(Error::from_kind::<T>)?;
+        self.restore_separator();
         Ok(None)
     }
+
+    /// Put the `--` back into the operands if it is followed by an argument
+    /// that would otherwise be passed on as an option
+    fn restore_separator(&mut self) {
+        let Some(separator) = self.separator.take() else {
+            return;
+        };
+        let position = self
+            .positional_arguments
+            .partition_point(|(index, _)| *index < separator);
+        let hyphen = self.positional_arguments[position..]
+            .iter()
+            .any(|(_, arg)| arg.as_encoded_bytes().starts_with(b"-") && arg != "-");
+        if hyphen {
+            self.positional_arguments
+                .insert(position, (separator, OsString::from("--")));
+        }
+    }
+
+    /// The index of the next argument that has not been consumed yet.
+    ///
+    /// This is only valid when the parser is not in the middle of parsing an
+    /// option, which is always true right after a positional argument.
+    fn next_index(&mut self) -> usize {
+        let remaining = self
+            .args
+            .parser
+            .try_raw_args()
+            .expect("parser should not be in the middle of an option")
+            .as_slice()
+            .len();
+        self.num_args - remaining
+    }
+}
+
+/// The result of [`Options::parse_capturing`]
+#[derive(Debug)]
+pub enum ParseOutcome<T> {
+    /// The options and operands were parsed
+    Parsed(T, Vec<OsString>),
+    /// The help was requested and has been written
+    Help,
+    /// The version was requested and has been written
+    Version,
 }
 
 /// Defines the app settings by consuming [`Arguments`].
@@ -164,23 +969,175 @@ impl<T: Arguments> ArgumentIter<T> {
 ///
 /// By default, the [`Options::parse`] method iterate over the arguments and
 /// call [`Options::apply`] on the result until the arguments are exhausted.
+///
+/// If `--help` or `--version` is passed, parsing stops with an
+/// [`ErrorKind::HelpRequested`] or [`ErrorKind::VersionRequested`] error
+/// containing the text to print. These errors have exit code 0.
+#[cfg_attr(
+    feature = "std-io",
+    doc = "Use [`Options::parse_or_exit`] to print the help, version or error and exit."
+)]
+///
+/// A bare `--` ends the processing of options: all arguments after it are
+/// returned as operands, even if they start with a hyphen. The `--` itself
+/// is not included in the operands.
 pub trait Options<Arg: Arguments>: Sized {
     /// Apply a single argument to the options.
     fn apply(&mut self, arg: Arg);
 
+    /// Apply a single argument to the options, given the index in the
+    /// arguments where it was found
+    ///
+    /// The binary name has index 0 and options in a cluster of short options
+    /// like `-abc` all have the same index. This is useful for utilities
+    /// where the relative order of options matters. By default, the index is
+    /// ignored and the argument is passed to [`Options::apply`].
+    fn apply_indexed(&mut self, _index: usize, arg: Arg) {
+        self.apply(arg);
+    }
+
+    /// Check that the options are consistent after all arguments have been
+    /// applied
+    ///
+    /// This is called by [`Options::parse`] and the other parsing methods
+    /// after the last call to [`Options::apply`], including the one for the
+    /// `#[arg(operands)]` variant. It is the place for checks that involve
+    /// multiple options, like options that cannot be combined. Errors should
+    /// usually have [`ErrorKind::Validation`]. By default, all options are
+    /// accepted.
+    fn validate(&self) -> Result<(), Error> {
+        Ok(())
+    }
+
     /// Parse an iterator of arguments into the options
-    #[allow(unused_mut)]
     fn parse<I>(mut self, args: I) -> Result<(Self, Vec<OsString>), Error>
     where
         I: IntoIterator,
         I::Item: Into<OsString>,
     {
-        // Hacky but it works: if the parse-is-complete flag is active the
-        // parse function becomes the complete function so that no additional
-        // functionality is necessary for users to generate completions. It is
-        // important that we exit the program here, because the program does
-        // not expect us to print the completion here and therefore will behave
-        // incorrectly.
+        let operands = self.parse_into(args)?;
+        Ok((self, operands))
+    }
+
+    /// Parse an iterator of arguments into the options or exit
+    ///
+    /// This is like [`Options::parse`], but if `--help` or `--version` is
+    /// passed, the help or version is printed to stdout and the program
+    /// exits with exit code 0. Any other error is printed to stderr and the
+    /// program exits with the exit code of the error.
+    ///
+    /// This requires the `std-io` feature. Without it, use
+    /// [`Options::parse`] or [`Options::parse_capturing`] instead.
+    #[cfg(feature = "std-io")]
+    fn parse_or_exit<I>(self, args: I) -> (Self, Vec<OsString>)
+    where
+        I: IntoIterator,
+        I::Item: Into<OsString>,
+    {
+        match self.parse(args) {
+            Ok(parsed) => parsed,
+            Err(err) => err.exit(),
+        }
+    }
+
+    /// Parse an iterator of arguments into the options, writing the help or
+    /// version to `out`
+    ///
+    /// This is like [`Options::parse`], but if `--help` or `--version` is
+    /// passed, the help or version is written to `out` and
+    /// [`ParseOutcome::Help`] or [`ParseOutcome::Version`] is returned
+    /// instead of an error. This makes it possible to check the output in
+    /// tests or to embed the command in another program. The help is written
+    /// without colors. Other errors are returned as usual.
+    fn parse_capturing<I>(self, args: I, out: &mut impl Write) -> Result<ParseOutcome<Self>, Error>
+    where
+        I: IntoIterator,
+        I::Item: Into<OsString>,
+    {
+        let (text, outcome) = match self.parse(args) {
+            Ok((options, operands)) => return Ok(ParseOutcome::Parsed(options, operands)),
+            Err(Error {
+                kind: ErrorKind::HelpRequested(help),
+                ..
+            }) => (help, ParseOutcome::Help),
+            Err(Error {
+                kind: ErrorKind::VersionRequested(version),
+                ..
+            }) => (version + "\n", ParseOutcome::Version),
+            Err(err) => return Err(err),
+        };
+        out.write_all(text.as_bytes())
+            .map_err(|err| Error::from_kind::<Arg>(err.into()))?;
+        Ok(outcome)
+    }
+
+    /// Parse an iterator of arguments into existing options
+    ///
+    /// This is like [`Options::parse`], but applies the arguments onto
+    /// `self` instead of consuming it and only returns the operands. This
+    /// makes it easy to build the options in layers, for example by first
+    /// applying the values from a configuration file and then the command
+    /// line arguments.
+    fn parse_into<I>(&mut self, args: I) -> Result<Vec<OsString>, Error>
+    where
+        I: IntoIterator,
+        I::Item: Into<OsString>,
+    {
+        let operands = parse_with_indices(self, args, true)?;
+        Ok(operands.into_iter().map(|(_, operand)| operand).collect())
+    }
+
+    /// Parse an iterator of arguments that does not start with the binary
+    /// name into the options
+    ///
+    /// This is like [`Options::parse`], but the first argument is parsed
+    /// like all others instead of being taken as the name of the binary.
+    /// This is useful for arguments that do not come from the command line,
+    /// such as a line in a configuration file, so that no placeholder for
+    /// the binary name needs to be added.
+    fn parse_without_bin_name<I>(mut self, args: I) -> Result<(Self, Vec<OsString>), Error>
+    where
+        I: IntoIterator,
+        I::Item: Into<OsString>,
+    {
+        let operands = parse_with_indices(&mut self, args, false)?;
+        Ok((
+            self,
+            operands.into_iter().map(|(_, operand)| operand).collect(),
+        ))
+    }
+
+    /// Parse an iterator of arguments into the options, keeping the index of
+    /// each operand
+    ///
+    /// This is like [`Options::parse`], but each operand is paired with its
+    /// index in `args`, where the binary name has index 0. This is useful for
+    /// diagnostics and for utilities that depend on the relative order of
+    /// options and operands.
+    fn parse_indexed<I>(mut self, args: I) -> Result<(Self, Vec<(usize, OsString)>), Error>
+    where
+        I: IntoIterator,
+        I::Item: Into<OsString>,
+    {
+        let operands = parse_with_indices(&mut self, args, true)?;
+        Ok((self, operands))
+    }
+
+    /// Parse options until the first operand and return the remaining
+    /// arguments untouched
+    ///
+    /// Unlike [`Options::parse`], parsing stops at the first operand. That
+    /// operand and all arguments after it are returned as they were given,
+    /// even if they look like options. This is useful for utilities like
+    /// `env` and `xargs`, which pass the rest of their arguments on to
+    /// another command. A `--` before the first operand ends the options and
+    /// is not returned. The `#[arg(operands)]` variant is not applied.
+    fn parse_until_operand<I>(self, args: I) -> Result<(Self, std::vec::IntoIter<OsString>), Error>
+    where
+        I: IntoIterator,
+        I::Item: Into<OsString>,
+    {
+        // See `parse_with_indices` for why this prints the completion.
         #[cfg(feature = "parse-is-complete")]
         {
             print_complete::<_, Self, Arg>(args.into_iter());
@@ -189,20 +1146,111 @@ pub trait Options<Arg: Arguments>: Sized {
 
         #[cfg(not(feature = "parse-is-complete"))]
         {
-            let mut iter = ArgumentIter::<Arg>::from_args(args);
-            while let Some(arg) = iter.next_arg()? {
-                self.apply(arg);
+            let mut options = self;
+            let args = expand_args::<Arg, _>(args, true)?;
+            let num_args = args.len();
+            let mut iter = ArgIterator::<Arg>::from_args(args);
+            let mut rest = Vec::new();
+            let mut seen = Vec::new();
+            let mut option_index = 0;
+            loop {
+                // Options in a cluster of short options share the index of
+                // the argument they are in, like in `ArgumentIter`.
+                if let Some(raw) = iter.parser.try_raw_args() {
+                    option_index = num_args - raw.as_slice().len();
+                }
+                let Some(arg) = iter.next_arg()? else {
+                    break;
+                };
+                help_or_version_error(&iter, &arg)?;
+                match arg {
+                    Argument::Help | Argument::Version => unreachable!(),
+                    Argument::Custom(arg) => {
+                        seen.push(arg.variant_name());
+                        options.apply_indexed(option_index, arg);
+                    }
+                    Argument::Positional(arg) | Argument::Subcommand { name: arg, .. } => {
+                        rest.push(arg);
+                        break;
+                    }
+                    Argument::MultiPositional(args) => {
+                        rest = args;
+                        break;
+                    }
+                    Argument::Unknown(mut arg) => {
+                        // Restore the rest of a cluster of short options
+                        if let Some(cluster) = iter.parser.optional_value() {
+                            arg.push(cluster);
+                        }
+                        rest.push(arg);
+                        break;
+                    }
+                }
             }
-            Ok((self, iter.positional_arguments))
+            if let Some(raw) = iter.parser.try_raw_args() {
+                rest.extend(raw);
+            }
+            check_required_groups::<Arg>(&seen).map_err(Error::from_kind::<Arg>)?;
+            options.validate()?;
+            Ok((options, rest.into_iter()))
         }
     }
 
     #[cfg(feature = "complete")]
-    fn complete(shell: &str) -> String {
+    fn complete(shell: &str) -> Result<String, uutils_args_complete::Error> {
         uutils_args_complete::render(&Arg::complete(), shell)
     }
 }
 
+fn parse_with_indices<I, O: Options<Arg>, Arg: Arguments>(
+    #[allow(unused_variables)] options: &mut O,
+    args: I,
+    has_bin_name: bool,
+) -> Result<Vec<(usize, OsString)>, Error>
+where
+    I: IntoIterator,
+    I::Item: Into<OsString>,
+{
+    // Hacky but it works: if the parse-is-complete flag is active the
+    // parse function becomes the complete function so that no additional
+    // functionality is necessary for users to generate completions. It is
+    // important that we exit the program here, because the program does
+    // not expect us to print the completion here and therefore will behave
+    // incorrectly.
+    #[cfg(feature = "parse-is-complete")]
+    {
+        let bin_name = (!has_bin_name).then(OsString::new);
+        print_complete::<_, O, Arg>(bin_name.into_iter().chain(args.into_iter().map(Into::into)));
+        std::process::exit(0);
+    }
+
+    #[cfg(not(feature = "parse-is-complete"))]
+    {
+        let mut iter = ArgumentIter::<Arg>::from_args(args, has_bin_name)?;
+        while let Some((index, arg)) = iter.next_arg()? {
+            options.apply_indexed(index, arg);
+        }
+
+        let operands: Vec<_> = iter
+            .positional_arguments
+            .iter()
+            .map(|(_, operand)| operand.clone())
+            .collect();
+        let arg = Arg::operands(&operands).map_err(Error::from_kind::<Arg>)?;
+        if let Some(arg) = arg {
+            let index = iter
+                .positional_arguments
+                .first()
+                .map_or(iter.num_args, |(index, _)| *index);
+            options.apply_indexed(index, arg);
+            options.validate()?;
+            return Ok(Vec::new());
+        }
+        options.validate()?;
+        Ok(iter.positional_arguments)
+    }
+}
+
 #[cfg(feature = "parse-is-complete")]
 fn print_complete<I, O: Options<Arg>, Arg: Arguments>(mut args: I)
 where
@@ -216,5 +1264,11 @@ where
         .into();
     let shell = shell.to_string_lossy();
     assert!(args.next().is_none(), "completion only takes one argument");
-    println!("{}", O::complete(&shell));
+    match O::complete(&shell) {
+        Ok(completion) => println!("{completion}"),
+        Err(err) => {
+            eprintln!("{err}");
+            std::process::exit(1);
+        }
+    }
 }
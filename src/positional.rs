@@ -1,7 +1,8 @@
 //! Parsing of positional arguments.
 //!
 //! The signature for parsing positional arguments is one of `&'static str`,
-//! [`Opt`], [`Many0`], [`Many1`] or a tuple of those. The [`Unpack::unpack`]
+//! [`Opt`], [`Many0`], [`Many1`], an array of `&'static str` or a tuple of
+//! those. The [`Unpack::unpack`]
 //! method of these types parses a `Vec<T>` into the corresponding
 //! [`Unpack::Output<T>`] type.
 //!
@@ -18,10 +19,10 @@
 //! assert_eq!(a, "one");
 //! assert_eq!(b, Some("two"));
 //!
-//! // It works for any `Vec<T>`:
-//! let (a, b) = ("FILE1", Opt("FILE2")).unpack(vec![1, 2]).unwrap();
-//! assert_eq!(a, 1);
-//! assert_eq!(b, Some(2));
+//! // It works for any `Vec<T>` of strings or paths:
+//! let (a, b) = ("FILE1", Opt("FILE2")).unpack(vec![OsString::from("one")]).unwrap();
+//! assert_eq!(a, "one");
+//! assert_eq!(b, None);
 //! ```
 //!
 //! Here are a few examples:
@@ -33,6 +34,7 @@
 //! Many1("FOO")     // one or more arguments with output `Vec<OsString>`
 //! Many0("FOO")     // zero or more arguments with output `Vec<OsString>`
 //! ("FOO", "FOO")   // two required arguments with output (`OsString`, `OsString`)
+//! ["FOO", "BAR"]   // two required arguments with output `[OsString; 2]`
 //! ```
 //!
 //! This allows for the construction of complex signatures. The signature
@@ -53,9 +55,28 @@
 //!
 //! does not make sense, because it's unclear where the positional arguments
 //! should go. The supported tuples implement [`Unpack`].
+//!
+//! Positional arguments are not declared in the [`Arguments`] derive. Earlier
+//! versions used attributes like `#[arg("FILE", 1)]` for this, which
+//! correspond to the following signatures:
+//!
+//! ```ignore
+//! #[arg("FOO", 1)]      // "FOO"
+//! #[arg("FOO", 0..=1)]  // Opt("FOO")
+//! #[arg("FOO", 1..)]    // Many1("FOO")
+//! #[arg("FOO", ..)]     // Many0("FOO")
+//! ```
+//!
+//! Missing required arguments result in
+//! [`ErrorKind::MissingPositionalArguments`]. These are usage errors with
+//! exit code 1. Use [`Unpack::unpack_for`] to get the exit code of an
+//! [`Arguments`] type instead. There is no equivalent of the `last` modifier,
+//! because the operands are unpacked after parsing, when it is no longer
+//! known which of them came after `--`.
 
 use crate::error::{Error, ErrorKind};
-use std::fmt::Debug;
+use crate::Arguments;
+use std::ffi::OsStr;
 
 /// A required argument
 type Req = &'static str;
@@ -71,16 +92,30 @@ pub struct Many0(pub Req);
 
 /// Unpack a `Vec` into the output type
 ///
-/// See the [module documentation](crate::positional) for more information.
+/// The operands must implement `AsRef<OsStr>`, so that an operand that does
+/// not fit the signature can be reported exactly in the error. See the
+/// [module documentation](crate::positional) for more information.
 pub trait Unpack {
     type Output<T>;
-    fn unpack<T: Debug>(&self, operands: Vec<T>) -> Result<Self::Output<T>, Error>;
+    fn unpack<T: AsRef<OsStr>>(&self, operands: Vec<T>) -> Result<Self::Output<T>, Error>;
+
+    /// Unpack a `Vec` with the exit codes of `A`
+    ///
+    /// This is like [`Unpack::unpack`], but the error gets the exit code that
+    /// `A` uses for it, see [`Error::from_kind`].
+    fn unpack_for<A: Arguments, T: AsRef<OsStr>>(
+        &self,
+        operands: Vec<T>,
+    ) -> Result<Self::Output<T>, Error> {
+        self.unpack(operands)
+            .map_err(|err| Error::from_kind::<A>(err.kind))
+    }
 }
 
 impl Unpack for () {
     type Output<T> = ();
 
-    fn unpack<T: Debug>(&self, operands: Vec<T>) -> Result<Self::Output<T>, Error> {
+    fn unpack<T: AsRef<OsStr>>(&self, operands: Vec<T>) -> Result<Self::Output<T>, Error> {
         assert_empty(operands)
     }
 }
@@ -88,7 +123,7 @@ impl Unpack for () {
 impl<U: Unpack> Unpack for (U,) {
     type Output<T> = U::Output<T>;
 
-    fn unpack<T: Debug>(&self, operands: Vec<T>) -> Result<Self::Output<T>, Error> {
+    fn unpack<T: AsRef<OsStr>>(&self, operands: Vec<T>) -> Result<Self::Output<T>, Error> {
         self.0.unpack(operands)
     }
 }
@@ -96,9 +131,9 @@ impl<U: Unpack> Unpack for (U,) {
 impl Unpack for Req {
     type Output<T> = T;
 
-    fn unpack<T: Debug>(&self, mut operands: Vec<T>) -> Result<Self::Output<T>, Error> {
+    fn unpack<T: AsRef<OsStr>>(&self, mut operands: Vec<T>) -> Result<Self::Output<T>, Error> {
         let arg = pop_front(self, &mut operands)?;
-        assert_empty(operands)?;
+        shift_too_many(1, assert_empty(operands))?;
         Ok(arg)
     }
 }
@@ -106,7 +141,7 @@ impl Unpack for Req {
 impl<U: Unpack> Unpack for Opt<U> {
     type Output<T> = Option<U::Output<T>>;
 
-    fn unpack<T: Debug>(&self, operands: Vec<T>) -> Result<Self::Output<T>, Error> {
+    fn unpack<T: AsRef<OsStr>>(&self, operands: Vec<T>) -> Result<Self::Output<T>, Error> {
         Ok(if operands.is_empty() {
             None
         } else {
@@ -118,7 +153,7 @@ impl<U: Unpack> Unpack for Opt<U> {
 impl Unpack for Many0 {
     type Output<T> = Vec<T>;
 
-    fn unpack<T: Debug>(&self, operands: Vec<T>) -> Result<Self::Output<T>, Error> {
+    fn unpack<T: AsRef<OsStr>>(&self, operands: Vec<T>) -> Result<Self::Output<T>, Error> {
         Ok(operands)
     }
 }
@@ -126,23 +161,41 @@ impl Unpack for Many0 {
 impl Unpack for Many1 {
     type Output<T> = Vec<T>;
 
-    fn unpack<T: Debug>(&self, operands: Vec<T>) -> Result<Self::Output<T>, Error> {
+    fn unpack<T: AsRef<OsStr>>(&self, operands: Vec<T>) -> Result<Self::Output<T>, Error> {
         if operands.is_empty() {
-            return Err(Error {
-                exit_code: 1,
-                kind: ErrorKind::MissingPositionalArguments(vec![self.0.into()]),
-            });
+            return Err(usage_error(ErrorKind::MissingPositionalArguments(vec![
+                self.0.into(),
+            ])));
         }
         Ok(operands)
     }
 }
 
+impl<const N: usize> Unpack for [Req; N] {
+    type Output<T> = [T; N];
+
+    fn unpack<T: AsRef<OsStr>>(&self, mut operands: Vec<T>) -> Result<Self::Output<T>, Error> {
+        if operands.len() < N {
+            return Err(usage_error(ErrorKind::MissingPositionalArguments(
+                self[operands.len()..]
+                    .iter()
+                    .map(|s| s.to_string())
+                    .collect(),
+            )));
+        }
+        let rest = operands.split_off(N);
+        shift_too_many(N, assert_empty(rest))?;
+        // The length was checked above, so this conversion cannot fail.
+        Ok(operands.try_into().unwrap_or_else(|_| unreachable!()))
+    }
+}
+
 impl<U: Unpack> Unpack for (Req, U) {
     type Output<T> = (T, U::Output<T>);
 
-    fn unpack<T: Debug>(&self, mut operands: Vec<T>) -> Result<Self::Output<T>, Error> {
+    fn unpack<T: AsRef<OsStr>>(&self, mut operands: Vec<T>) -> Result<Self::Output<T>, Error> {
         let arg = pop_front(self.0, &mut operands)?;
-        let rest = self.1.unpack(operands)?;
+        let rest = shift_too_many(1, self.1.unpack(operands))?;
         Ok((arg, rest))
     }
 }
@@ -150,10 +203,10 @@ impl<U: Unpack> Unpack for (Req, U) {
 impl<U: Unpack> Unpack for (Req, Req, U) {
     type Output<T> = (T, T, U::Output<T>);
 
-    fn unpack<T: Debug>(&self, mut operands: Vec<T>) -> Result<Self::Output<T>, Error> {
+    fn unpack<T: AsRef<OsStr>>(&self, mut operands: Vec<T>) -> Result<Self::Output<T>, Error> {
         let arg1 = pop_front(self.0, &mut operands)?;
         let arg2 = pop_front(self.1, &mut operands)?;
-        let rest = self.2.unpack(operands)?;
+        let rest = shift_too_many(2, self.2.unpack(operands))?;
         Ok((arg1, arg2, rest))
     }
 }
@@ -161,7 +214,24 @@ impl<U: Unpack> Unpack for (Req, Req, U) {
 impl<U: Unpack> Unpack for (Opt<U>, Req) {
     type Output<T> = (Option<<U as Unpack>::Output<T>>, T);
 
-    fn unpack<T: Debug>(&self, mut operands: Vec<T>) -> Result<Self::Output<T>, Error> {
+    fn unpack<T: AsRef<OsStr>>(&self, mut operands: Vec<T>) -> Result<Self::Output<T>, Error> {
+        // The operands are assigned from the front, so the first extra
+        // operand comes right after the ones the signature accepts, which
+        // can be the last one. The structure is checked on placeholders
+        // first, so that this operand can still be named.
+        let got = operands.len();
+        let placeholders = vec![""; got.saturating_sub(1)];
+        if let Err(Error {
+            kind: ErrorKind::TooManyPositionalArguments { expected, .. },
+            ..
+        }) = self.0.unpack(placeholders)
+        {
+            return Err(usage_error(ErrorKind::TooManyPositionalArguments {
+                expected: expected + 1,
+                got,
+                first_extra: operands[expected + 1].as_ref().into(),
+            }));
+        }
         let arg = pop_back(self.1, &mut operands)?;
         let rest = self.0.unpack(operands)?;
         Ok((rest, arg))
@@ -171,9 +241,9 @@ impl<U: Unpack> Unpack for (Opt<U>, Req) {
 impl Unpack for (Many0, Req) {
     type Output<T> = (Vec<T>, T);
 
-    fn unpack<T: Debug>(&self, mut operands: Vec<T>) -> Result<Self::Output<T>, Error> {
+    fn unpack<T: AsRef<OsStr>>(&self, mut operands: Vec<T>) -> Result<Self::Output<T>, Error> {
         let arg = pop_back(self.1, &mut operands)?;
-        let rest = self.0.unpack(operands)?;
+        let rest = shift_too_many(1, self.0.unpack(operands))?;
         Ok((rest, arg))
     }
 }
@@ -181,43 +251,85 @@ impl Unpack for (Many0, Req) {
 impl Unpack for (Many1, Req) {
     type Output<T> = (Vec<T>, T);
 
-    fn unpack<T: Debug>(&self, mut operands: Vec<T>) -> Result<Self::Output<T>, Error> {
+    fn unpack<T: AsRef<OsStr>>(&self, mut operands: Vec<T>) -> Result<Self::Output<T>, Error> {
         let arg = pop_back(self.1, &mut operands)?;
-        let rest = self.0.unpack(operands)?;
+        let rest = shift_too_many(1, self.0.unpack(operands))?;
         Ok((rest, arg))
     }
 }
 
-fn pop_front<T: Debug>(name: &str, operands: &mut Vec<T>) -> Result<T, Error> {
+/// Check that at least `n` operands were given
+///
+/// This is like [`Many1`], but with any minimum. For example, `cp` needs at
+/// least two files. If there are fewer operands, the error is
+/// [`ErrorKind::MissingPositionalArguments`] with `name`, so that the
+/// message can say which operand is missing.
+///
+/// ```
+/// use uutils_args::positional::require_at_least;
+///
+/// assert!(require_at_least(&["a", "b"], 2, "FILE").is_ok());
+/// assert!(require_at_least(&["a"], 2, "FILE").is_err());
+/// ```
+pub fn require_at_least<T>(operands: &[T], n: usize, name: &str) -> Result<(), Error> {
+    if operands.len() < n {
+        return Err(usage_error(ErrorKind::MissingPositionalArguments(vec![
+            name.to_string(),
+        ])));
+    }
+    Ok(())
+}
+
+fn pop_front<T>(name: &str, operands: &mut Vec<T>) -> Result<T, Error> {
     if operands.is_empty() {
-        return Err(Error {
-            exit_code: 1,
-            kind: ErrorKind::MissingPositionalArguments(vec![name.to_string()]),
-        });
+        return Err(usage_error(ErrorKind::MissingPositionalArguments(vec![
+            name.to_string(),
+        ])));
     }
     Ok(operands.remove(0))
 }
 
-fn pop_back<T: Debug>(name: &str, operands: &mut Vec<T>) -> Result<T, Error> {
-    operands.pop().ok_or_else(|| Error {
-        exit_code: 1,
-        kind: ErrorKind::MissingPositionalArguments(vec![name.to_string()]),
+fn pop_back<T>(name: &str, operands: &mut Vec<T>) -> Result<T, Error> {
+    operands.pop().ok_or_else(|| {
+        usage_error(ErrorKind::MissingPositionalArguments(
+            vec![name.to_string()],
+        ))
     })
 }
 
-fn assert_empty<T: Debug>(mut operands: Vec<T>) -> Result<(), Error> {
-    if let Some(arg) = operands.pop() {
-        return Err(Error {
-            exit_code: 1,
-            kind: ErrorKind::UnexpectedArgument(format!("{:?}", arg)),
-        });
+fn assert_empty<T: AsRef<OsStr>>(operands: Vec<T>) -> Result<(), Error> {
+    let got = operands.len();
+    if let Some(arg) = operands.into_iter().next() {
+        return Err(usage_error(ErrorKind::TooManyPositionalArguments {
+            expected: 0,
+            got,
+            first_extra: arg.as_ref().into(),
+        }));
     }
     Ok(())
 }
 
+/// Create an error with exit code 1, the default for usage errors
+fn usage_error(kind: ErrorKind) -> Error {
+    Error { exit_code: 1, kind }
+}
+
+/// Account for `n` operands that were already consumed by the caller in
+/// the counts of an [`ErrorKind::TooManyPositionalArguments`] error.
+fn shift_too_many<T>(n: usize, result: Result<T, Error>) -> Result<T, Error> {
+    result.map_err(|mut err| {
+        if let ErrorKind::TooManyPositionalArguments { expected, got, .. } = &mut err.kind {
+            *expected += n;
+            *got += n;
+        }
+        err
+    })
+}
+
 #[cfg(test)]
 mod test {
-    use super::{Many0, Many1, Opt, Unpack};
+    use super::{require_at_least, Many0, Many1, Opt, Unpack};
+    use crate::ErrorKind;
 
     macro_rules! a {
         ($e:expr, $t:ty) => {
@@ -242,6 +354,28 @@ mod test {
         assert!(signature.unpack(operands).is_err());
     }
 
+    #[track_caller]
+    fn assert_too_many<const N: usize>(
+        signature: &impl Unpack,
+        operands: [&str; N],
+        expected: usize,
+        first_extra: &str,
+    ) {
+        let operands = Vec::from(operands);
+        match signature.unpack(operands).map(|_| ()).unwrap_err().kind {
+            ErrorKind::TooManyPositionalArguments {
+                expected: e,
+                got: g,
+                first_extra: f,
+            } => {
+                assert_eq!(e, expected);
+                assert_eq!(g, N);
+                assert_eq!(f, first_extra);
+            }
+            _ => panic!("wrong error kind"),
+        }
+    }
+
     #[test]
     fn compile_tests() {
         // The five basic ones
@@ -250,6 +384,8 @@ mod test {
         a!(Opt("FOO"), Option<&str>);
         a!(Many0("FOO"), Vec<&str>);
         a!(Many1("FOO"), Vec<&str>);
+        a!([], [&str; 0]);
+        a!(["FOO", "BAR"], [&str; 2]);
 
         // Start building some tuples
         a!(("FOO", "BAR"), (&str, &str));
@@ -330,6 +466,31 @@ mod test {
         assert_ok(&s, vec!["foo", "bar", "baz"], ["foo", "bar", "baz"]);
     }
 
+    #[test]
+    fn array() {
+        let s: [&str; 0] = [];
+        assert_ok(&s, [], []);
+        assert_err(&s, ["foo"]);
+        assert_too_many(&s, ["foo", "bar"], 0, "foo");
+
+        let s = ["FOO", "BAR"];
+        assert_ok(&s, ["foo", "bar"], ["foo", "bar"]);
+        assert_too_many(&s, ["foo", "bar", "baz"], 2, "baz");
+        assert_too_many(&s, ["foo", "bar", "baz", "qux"], 2, "baz");
+    }
+
+    #[test]
+    fn array_missing() {
+        let s = ["FOO", "BAR", "BAZ"];
+        let names = |operands: Vec<&str>| match s.unpack(operands).unwrap_err().kind {
+            ErrorKind::MissingPositionalArguments(names) => names,
+            _ => panic!("wrong error kind"),
+        };
+        assert_eq!(names(vec![]), ["FOO", "BAR", "BAZ"]);
+        assert_eq!(names(vec!["foo"]), ["BAR", "BAZ"]);
+        assert_eq!(names(vec!["foo", "bar"]), ["BAZ"]);
+    }
+
     #[test]
     fn req_req() {
         let s = ("FOO", "BAR");
@@ -349,6 +510,58 @@ mod test {
         assert_err(&s, ["1", "2", "3", "4"]);
     }
 
+    #[test]
+    fn too_many() {
+        assert_too_many(&(), ["foo", "bar"], 0, "foo");
+        assert_too_many(&"FOO", ["foo", "bar", "baz"], 1, "bar");
+        assert_too_many(&Opt("FOO"), ["foo", "bar"], 1, "bar");
+        assert_too_many(&("FOO", "BAR"), ["a", "b", "c", "d"], 2, "c");
+        assert_too_many(
+            &(Opt(("FIRST", Opt("INCREMENT"))), "LAST"),
+            ["1", "2", "3", "4"],
+            3,
+            "4",
+        );
+        assert_too_many(
+            &(Opt(("FIRST", Opt("INCREMENT"))), "LAST"),
+            ["1", "2", "3", "4", "5"],
+            3,
+            "4",
+        );
+        assert_too_many(&(Opt("FOO"), "BAR"), ["a", "b", "c"], 2, "c");
+        assert_too_many(&("FOO", "BAR", Opt("BAZ")), ["a", "b", "c", "d"], 3, "d");
+
+        let err = "FOO".unpack(vec!["a", "b \"c\""]).unwrap_err();
+        assert!(err
+            .to_string()
+            .ends_with("The first unexpected argument is 'b \"c\"'."));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn too_many_non_utf8() {
+        use std::{ffi::OsStr, os::unix::ffi::OsStrExt};
+
+        let extra = OsStr::from_bytes(b"b\xff");
+        let err = "FOO".unpack(vec![OsStr::new("a"), extra]).unwrap_err();
+        match err.kind {
+            ErrorKind::TooManyPositionalArguments { first_extra, .. } => {
+                assert_eq!(first_extra, extra);
+            }
+            _ => panic!("wrong error kind"),
+        }
+    }
+
+    #[test]
+    fn at_least() {
+        assert!(require_at_least::<&str>(&[], 0, "FILE").is_ok());
+        assert!(require_at_least(&["a", "b", "c"], 2, "FILE").is_ok());
+        let err = require_at_least(&["a"], 2, "FILE").unwrap_err();
+        assert!(
+            matches!(err.kind, ErrorKind::MissingPositionalArguments(names) if names == ["FILE"])
+        );
+    }
+
     #[test]
     fn mknod() {
         let s = ("NAME", "TYPE", Opt(("MAJOR", "MINOR")));
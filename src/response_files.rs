@@ -0,0 +1,92 @@
+// For the full copyright and license information, please view the LICENSE
+// file that was distributed with this source code.
+
+//! Expansion of `@file` arguments, see `#[arguments(response_files)]`.
+
+use crate::error::ErrorKind;
+use std::{
+    ffi::{OsStr, OsString},
+    io,
+};
+
+/// How deeply response files can include other response files
+///
+/// This protects against a response file that includes itself.
+const MAX_DEPTH: usize = 10;
+
+/// Replace every `@file` argument with the lines of `file`
+///
+/// The binary name, if there is one, and the arguments after `--` are not
+/// expanded. A lone `@` is not a response file. Response files may contain
+/// `@file` arguments themselves, up to [`MAX_DEPTH`] levels deep.
+pub(crate) fn expand(args: Vec<OsString>, has_bin_name: bool) -> Result<Vec<OsString>, ErrorKind> {
+    let mut args = args.into_iter();
+    let mut expanded = Vec::new();
+    if has_bin_name {
+        expanded.extend(args.next());
+    }
+    let mut options_ended = false;
+    expand_into(&mut expanded, args, 0, &mut options_ended)?;
+    Ok(expanded)
+}
+
+fn expand_into(
+    expanded: &mut Vec<OsString>,
+    args: impl IntoIterator<Item = OsString>,
+    depth: usize,
+    options_ended: &mut bool,
+) -> Result<(), ErrorKind> {
+    for arg in args {
+        if *options_ended {
+            expanded.push(arg);
+            continue;
+        }
+        if arg == "--" {
+            *options_ended = true;
+        }
+        let Some(file) = arg
+            .as_encoded_bytes()
+            .strip_prefix(b"@")
+            .filter(|file| !file.is_empty())
+        else {
+            expanded.push(arg);
+            continue;
+        };
+        // SAFETY: The bytes are split right after an ASCII character, so
+        // they are still valid encoded bytes.
+        let file = unsafe { OsStr::from_encoded_bytes_unchecked(file) }.to_os_string();
+        let lines = if depth < MAX_DEPTH {
+            read_lines(&file)
+        } else {
+            Err(io::Error::other("too many nested response files"))
+        };
+        let lines = lines.map_err(|error| ErrorKind::ResponseFile { file, error })?;
+        expand_into(expanded, lines, depth + 1, options_ended)?;
+    }
+    Ok(())
+}
+
+/// Read a response file, which contains one argument per line
+fn read_lines(file: &OsString) -> io::Result<Vec<OsString>> {
+    let contents = std::fs::read(file)?;
+    let contents = contents.strip_suffix(b"\n").unwrap_or(&contents);
+    if contents.is_empty() {
+        return Ok(Vec::new());
+    }
+    contents.split(|b| *b == b'\n').map(to_os_string).collect()
+}
+
+#[cfg(unix)]
+fn to_os_string(line: &[u8]) -> io::Result<OsString> {
+    use std::os::unix::ffi::OsStringExt;
+    Ok(OsString::from_vec(line.to_vec()))
+}
+
+#[cfg(not(unix))]
+fn to_os_string(line: &[u8]) -> io::Result<OsString> {
+    // Files written on Windows usually have CRLF line endings
+    let line = line.strip_suffix(b"\r").unwrap_or(line);
+    String::from_utf8(line.to_vec())
+        .map(Into::into)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+}
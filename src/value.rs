@@ -4,7 +4,14 @@
 use crate::error::{Error, ErrorKind};
 use std::{
     ffi::{OsStr, OsString},
+    fmt::Display,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+    num::{
+        IntErrorKind, NonZeroI128, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8, NonZeroIsize,
+        NonZeroU128, NonZeroU16, NonZeroU32, NonZeroU64, NonZeroU8, NonZeroUsize, ParseIntError,
+    },
     path::PathBuf,
+    str::FromStr,
 };
 #[cfg(feature = "complete")]
 use uutils_args_complete::ValueHint;
@@ -18,6 +25,14 @@ pub enum ValueError {
         candidates: Vec<String>,
     },
     InvalidUnicode(OsString),
+    /// An integer does not fit in the type it is parsed into.
+    ///
+    /// `range` is the range of the type, like `0..=255` for `u8`.
+    OutOfRange {
+        value: String,
+        ty: &'static str,
+        range: String,
+    },
 }
 
 impl std::error::Error for ValueError {}
@@ -44,6 +59,9 @@ impl std::fmt::Display for ValueError {
             ValueError::InvalidUnicode(x) => {
                 write!(f, "'{}' is invalid unicode.", x.to_string_lossy())
             }
+            ValueError::OutOfRange { value, ty, range } => {
+                write!(f, "value {value} out of range for {ty} ({range})")
+            }
         }
     }
 }
@@ -54,6 +72,21 @@ impl std::fmt::Display for ValueError {
 pub trait Value: Sized {
     fn from_value(value: &OsStr) -> ValueResult<Self>;
 
+    /// Parse the value given to the option called `option`
+    ///
+    /// This can be implemented to mention the option in error messages. The
+    /// option is empty if the value does not belong to an option. By
+    /// default, the option is ignored and [`Value::from_value`] is called.
+    fn from_value_named(value: &OsStr, option: &str) -> ValueResult<Self> {
+        let _ = option;
+        Self::from_value(value)
+    }
+
+    /// The hint for completions of this value
+    ///
+    /// Types for dates and times, like the ones taken by `date -d` and
+    /// `touch -d`, should return [`ValueHint::DateTime`], so that shells do
+    /// not offer files for them.
     #[cfg(feature = "complete")]
     fn value_hint() -> ValueHint {
         ValueHint::Unknown
@@ -77,6 +110,108 @@ impl Value for PathBuf {
     }
 }
 
+/// The raw bytes of a value
+///
+/// This is useful for utilities like `printf` and `tr`, which operate on
+/// bytes and should not lose any data that is not valid UTF-8. On Unix, the
+/// bytes are exactly the bytes of the argument. On Windows, arguments are
+/// UTF-16, so the bytes are its lossless WTF-8 encoding, which is equal to
+/// UTF-8 for any valid Unicode.
+///
+/// ```
+/// use std::ffi::OsStr;
+/// use uutils_args::{Bytes, Value};
+///
+/// let Bytes(bytes) = Bytes::from_value(OsStr::new("a\\n")).unwrap();
+/// assert_eq!(bytes, b"a\\n");
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Bytes(pub Vec<u8>);
+
+impl Value for Bytes {
+    fn from_value(value: &OsStr) -> ValueResult<Self> {
+        Ok(Self(value.as_encoded_bytes().to_vec()))
+    }
+}
+
+/// A pair of a key and a value separated by `=`, like `NAME=VALUE`
+///
+/// This is a value for an option like `--define NAME=VALUE`, which is
+/// different from dd-style options like `if=FILE`. The value is split on
+/// the first `=`, so the value part can contain `=` too. A value without
+/// `=` is an error. Like [`OsStringList`], this does not require valid
+/// UTF-8.
+///
+/// ```
+/// use std::ffi::OsStr;
+/// use uutils_args::{KeyValue, Value};
+///
+/// let pair = KeyValue::from_value(OsStr::new("a=b=c")).unwrap();
+/// assert_eq!(pair.key, "a");
+/// assert_eq!(pair.value, "b=c");
+/// assert!(KeyValue::from_value(OsStr::new("abc")).is_err());
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct KeyValue {
+    pub key: OsString,
+    pub value: OsString,
+}
+
+impl Value for KeyValue {
+    fn from_value(value: &OsStr) -> ValueResult<Self> {
+        let bytes = value.as_encoded_bytes();
+        let Some(i) = bytes.iter().position(|&b| b == b'=') else {
+            return Err(
+                format!("'{}' is not of the form KEY=VALUE", value.to_string_lossy()).into(),
+            );
+        };
+        // SAFETY: The bytes come from an `OsStr` and are only split on an
+        // ASCII character, which is a valid boundary.
+        let (key, value) = unsafe {
+            (
+                OsStr::from_encoded_bytes_unchecked(&bytes[..i]),
+                OsStr::from_encoded_bytes_unchecked(&bytes[i + 1..]),
+            )
+        };
+        Ok(Self {
+            key: key.into(),
+            value: value.into(),
+        })
+    }
+}
+
+/// A path that must not be empty
+///
+/// Unlike [`PathBuf`], which accepts any value, an empty value is rejected
+/// when the argument is parsed, for example for `--output=`. This gives a
+/// clearer error than failing to open the empty path later.
+///
+/// ```
+/// use std::ffi::OsStr;
+/// use std::path::PathBuf;
+/// use uutils_args::{NonEmptyPath, Value};
+///
+/// let NonEmptyPath(path) = NonEmptyPath::from_value(OsStr::new("out.txt")).unwrap();
+/// assert_eq!(path, PathBuf::from("out.txt"));
+/// assert!(NonEmptyPath::from_value(OsStr::new("")).is_err());
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct NonEmptyPath(pub PathBuf);
+
+impl Value for NonEmptyPath {
+    fn from_value(value: &OsStr) -> ValueResult<Self> {
+        if value.is_empty() {
+            return Err("the path must not be empty".into());
+        }
+        Ok(Self(PathBuf::from(value)))
+    }
+
+    #[cfg(feature = "complete")]
+    fn value_hint() -> ValueHint {
+        ValueHint::AnyPath
+    }
+}
+
 impl Value for String {
     fn from_value(value: &OsStr) -> ValueResult<Self> {
         match value.to_str() {
@@ -90,6 +225,220 @@ impl Value for String {
     }
 }
 
+/// A list of values separated by `SEP`, like the entries in `$PATH`
+///
+/// Unlike parsing into a [`String`] and splitting that, the value is split
+/// without requiring valid UTF-8, so every entry is preserved exactly.
+///
+/// ```
+/// use std::ffi::{OsStr, OsString};
+/// use uutils_args::{OsStringList, Value};
+///
+/// let OsStringList(list) = OsStringList::<':'>::from_value(OsStr::new("/a:/b")).unwrap();
+/// assert_eq!(list, [OsString::from("/a"), OsString::from("/b")]);
+/// ```
+///
+/// The separator must be an ASCII character, which is checked at compile time:
+///
+/// ```compile_fail
+/// use std::ffi::OsStr;
+/// use uutils_args::{OsStringList, Value};
+///
+/// let _ = OsStringList::<'é'>::from_value(OsStr::new("a"));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct OsStringList<const SEP: char = ':'>(pub Vec<OsString>);
+
+impl<const SEP: char> Value for OsStringList<SEP> {
+    fn from_value(value: &OsStr) -> ValueResult<Self> {
+        const { assert!(SEP.is_ascii(), "separator of OsStringList must be ASCII") };
+        let list = value
+            .as_encoded_bytes()
+            .split(|&b| b == SEP as u8)
+            // SAFETY: The bytes come from an `OsStr` and are only split on
+            // an ASCII character, which is a valid boundary.
+            .map(|bytes| unsafe { OsStr::from_encoded_bytes_unchecked(bytes) }.into())
+            .collect();
+        Ok(Self(list))
+    }
+}
+
+/// A value parsed with the [`FromStr`] implementation of `T`
+///
+/// `Value` cannot be implemented for every type implementing `FromStr`,
+/// because that blanket implementation would conflict with the other
+/// implementations of `Value`. This wrapper can be used instead for types
+/// that already implement `FromStr`. The value must be valid UTF-8.
+///
+/// ```
+/// use std::ffi::OsStr;
+/// use uutils_args::{FromStrValue, Value};
+///
+/// let FromStrValue(c) = FromStrValue::<char>::from_value(OsStr::new("x")).unwrap();
+/// assert_eq!(c, 'x');
+/// assert!(FromStrValue::<char>::from_value(OsStr::new("xy")).is_err());
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FromStrValue<T>(pub T);
+
+impl<T> Value for FromStrValue<T>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    fn from_value(value: &OsStr) -> ValueResult<Self> {
+        let string = String::from_value(value)?;
+        match string.parse() {
+            Ok(v) => Ok(Self(v)),
+            Err(e) => Err(e.to_string().into()),
+        }
+    }
+}
+
+/// A size in bytes with an optional multiplier suffix
+///
+/// The suffixes `K`, `M`, `G`, `T`, `P`, `E`, `Z` and `Y` (optionally
+/// followed by `iB`) are powers of 1024, the same letters followed by `B`
+/// are powers of 1000 and `b` means 512. Like in GNU, `k` may be used instead
+/// of `K`. If the number is omitted but a suffix is given, the number
+/// defaults to 1, so `K` means 1024.
+///
+/// ```
+/// use std::ffi::OsStr;
+/// use uutils_args::{Size, Value};
+///
+/// let Size(n) = Size::from_value(OsStr::new("2KiB")).unwrap();
+/// assert_eq!(n, 2048);
+/// let Size(n) = Size::from_value(OsStr::new("1KB")).unwrap();
+/// assert_eq!(n, 1000);
+/// assert!(Size::from_value(OsStr::new("1X")).is_err());
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Size(pub u64);
+
+/// Error returned by [`Size::parse`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SizeError {
+    /// The number or suffix could not be parsed
+    Invalid(String),
+    /// The size does not fit in a `u64`
+    TooLarge(String),
+}
+
+impl std::error::Error for SizeError {}
+
+impl std::fmt::Display for SizeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SizeError::Invalid(s) => write!(f, "invalid size: '{s}'"),
+            SizeError::TooLarge(_) => write!(f, "Value too large for defined data type"),
+        }
+    }
+}
+
+impl Size {
+    /// Parse a size from a string
+    ///
+    /// Unlike [`Value::from_value`], this returns a [`SizeError`], so that
+    /// callers can replace the message for invalid sizes.
+    pub fn parse(s: &str) -> Result<Self, SizeError> {
+        let end_num = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+        let (num, suffix) = s.split_at(end_num);
+
+        let num: u64 = match (num, suffix) {
+            ("", "") => return Err(SizeError::Invalid(s.into())),
+            ("", _) => 1,
+            _ => num.parse().map_err(|_| SizeError::TooLarge(s.into()))?,
+        };
+
+        let multiplier = match suffix {
+            "" => Some(1),
+            "b" => Some(512),
+            "K" | "k" | "KiB" => Some(1024),
+            "M" | "MiB" => 1024_u64.checked_pow(2),
+            "G" | "GiB" => 1024_u64.checked_pow(3),
+            "T" | "TiB" => 1024_u64.checked_pow(4),
+            "P" | "PiB" => 1024_u64.checked_pow(5),
+            "E" | "EiB" => 1024_u64.checked_pow(6),
+            "Z" | "ZiB" => 1024_u64.checked_pow(7),
+            "Y" | "YiB" => 1024_u64.checked_pow(8),
+            "KB" | "kB" => Some(1000),
+            "MB" => 1000_u64.checked_pow(2),
+            "GB" => 1000_u64.checked_pow(3),
+            "TB" => 1000_u64.checked_pow(4),
+            "PB" => 1000_u64.checked_pow(5),
+            "EB" => 1000_u64.checked_pow(6),
+            "ZB" => 1000_u64.checked_pow(7),
+            "YB" => 1000_u64.checked_pow(8),
+            _ => return Err(SizeError::Invalid(s.into())),
+        };
+
+        match multiplier.and_then(|m| m.checked_mul(num)) {
+            Some(n) => Ok(Self(n)),
+            None => Err(SizeError::TooLarge(s.into())),
+        }
+    }
+}
+
+impl Value for Size {
+    fn from_value(value: &OsStr) -> ValueResult<Self> {
+        let string = String::from_value(value)?;
+        Ok(Self::parse(&string)?)
+    }
+
+    #[cfg(feature = "complete")]
+    fn value_hint() -> ValueHint {
+        ValueHint::Number
+    }
+}
+
+/// The block size of utilities like `du`, `df` and `ls`
+///
+/// This parses the value of `--block-size` in GNU's grammar. A size like
+/// `512`, `1K`, `1KiB`, `1KB` or `M` is parsed like [`Size`] and must not be
+/// zero. The special values `human-readable` and `si` select sizes with
+/// a unit in powers of 1024 and 1000, like the `-h` and `--si` flags. The
+/// `'` prefix for thousands separators is not supported. The default is
+/// 1024 bytes, which is used for an optional value that is not given.
+///
+/// ```
+/// use std::ffi::OsStr;
+/// use uutils_args::{BlockSize, Value};
+///
+/// assert_eq!(BlockSize::from_value(OsStr::new("1M")).unwrap(), BlockSize::Fixed(1024 * 1024));
+/// assert_eq!(BlockSize::from_value(OsStr::new("si")).unwrap(), BlockSize::Si);
+/// assert!(BlockSize::from_value(OsStr::new("0")).is_err());
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum BlockSize {
+    /// Sizes are shown in units of this many bytes
+    Fixed(u64),
+    /// Sizes are shown with a unit in powers of 1024
+    HumanReadable,
+    /// Sizes are shown with a unit in powers of 1000
+    Si,
+}
+
+impl Default for BlockSize {
+    fn default() -> Self {
+        Self::Fixed(1024)
+    }
+}
+
+impl Value for BlockSize {
+    fn from_value(value: &OsStr) -> ValueResult<Self> {
+        let string = String::from_value(value)?;
+        match string.as_str() {
+            "human-readable" => Ok(Self::HumanReadable),
+            "si" => Ok(Self::Si),
+            s => match Size::parse(s)? {
+                Size(0) => Err(SizeError::Invalid(s.into()).into()),
+                Size(n) => Ok(Self::Fixed(n)),
+            },
+        }
+    }
+}
+
 impl<T> Value for Option<T>
 where
     T: Value,
@@ -109,7 +458,24 @@ macro_rules! value_int {
         impl Value for $t {
             fn from_value(value: &OsStr) -> ValueResult<Self> {
                 let string = String::from_value(value)?;
-                Ok(string.parse()?)
+                string
+                    .parse()
+                    .map_err(|err: ParseIntError| match err.kind() {
+                        IntErrorKind::PosOverflow | IntErrorKind::NegOverflow => {
+                            ValueError::OutOfRange {
+                                value: string,
+                                ty: stringify!($t),
+                                range: format!("{}..={}", <$t>::MIN, <$t>::MAX),
+                            }
+                            .into()
+                        }
+                        _ => err.into(),
+                    })
+            }
+
+            #[cfg(feature = "complete")]
+            fn value_hint() -> ValueHint {
+                ValueHint::Number
             }
         }
     };
@@ -128,3 +494,54 @@ value_int!(i32);
 value_int!(i64);
 value_int!(i128);
 value_int!(isize);
+
+macro_rules! value_non_zero {
+    ($t: ty, $inner: ty) => {
+        impl Value for $t {
+            fn from_value(value: &OsStr) -> ValueResult<Self> {
+                let n = <$inner>::from_value(value)?;
+                <$t>::new(n).ok_or_else(|| "value must not be zero".into())
+            }
+
+            #[cfg(feature = "complete")]
+            fn value_hint() -> ValueHint {
+                ValueHint::Number
+            }
+        }
+    };
+}
+
+value_non_zero!(NonZeroU8, u8);
+value_non_zero!(NonZeroU16, u16);
+value_non_zero!(NonZeroU32, u32);
+value_non_zero!(NonZeroU64, u64);
+value_non_zero!(NonZeroU128, u128);
+value_non_zero!(NonZeroUsize, usize);
+
+value_non_zero!(NonZeroI8, i8);
+value_non_zero!(NonZeroI16, i16);
+value_non_zero!(NonZeroI32, i32);
+value_non_zero!(NonZeroI64, i64);
+value_non_zero!(NonZeroI128, i128);
+value_non_zero!(NonZeroIsize, isize);
+
+macro_rules! value_net {
+    ($t: ty, $hint: expr) => {
+        impl Value for $t {
+            fn from_value(value: &OsStr) -> ValueResult<Self> {
+                let string = String::from_value(value)?;
+                Ok(string.parse()?)
+            }
+
+            #[cfg(feature = "complete")]
+            fn value_hint() -> ValueHint {
+                $hint
+            }
+        }
+    };
+}
+
+value_net!(IpAddr, ValueHint::Hostname);
+value_net!(Ipv4Addr, ValueHint::Hostname);
+value_net!(Ipv6Addr, ValueHint::Hostname);
+value_net!(SocketAddr, ValueHint::Unknown);
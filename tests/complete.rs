@@ -0,0 +1,265 @@
+#![cfg(feature = "complete")]
+
+use uutils_args::{Arguments, Value};
+use uutils_args_complete::ValueHint;
+
+#[allow(dead_code)]
+#[derive(Value)]
+enum Format {
+    #[value]
+    Long,
+    #[value]
+    Short,
+}
+
+#[allow(dead_code)]
+#[derive(Arguments)]
+enum Arg {
+    #[arg("--format=FORMAT")]
+    Format(Format),
+
+    #[arg("-w WIDTH")]
+    Width(u32),
+
+    #[arg("-a", "--all")]
+    All,
+
+    #[arg("---presume-input-pipe", hidden)]
+    PresumeInputPipe,
+}
+
+#[test]
+fn value_hints() {
+    let command = Arg::complete();
+    let hints: Vec<_> = command.args.iter().map(|arg| &arg.value).collect();
+    assert!(matches!(
+        &hints[..],
+        [
+            Some(ValueHint::Strings(formats)),
+            Some(ValueHint::Number),
+            None,
+            None,
+            // --help and --version
+            None,
+            None,
+        ] if formats == &["long", "short"]
+    ));
+}
+
+#[test]
+fn dd_style_value_hint() {
+    // A dd-style flag always takes a value, even if the other flags do not
+    #[allow(dead_code)]
+    #[derive(Arguments)]
+    enum Arg {
+        #[arg("-s", "size=N", value = 0)]
+        Size(u64),
+    }
+
+    let command = Arg::complete();
+    assert!(matches!(command.args[0].value, Some(ValueHint::Number)));
+}
+
+#[test]
+fn hidden_args() {
+    let command = Arg::complete();
+    for shell in ["fish", "man"] {
+        let out = uutils_args_complete::render(&command, shell).unwrap();
+        assert!(out.contains("all"));
+        assert!(!out.contains("presume-input-pipe"));
+    }
+}
+
+#[test]
+fn hidden_from_help_or_completions() {
+    #[allow(dead_code)]
+    #[derive(Arguments)]
+    enum Arg {
+        #[arg("--power-user", hidden_help)]
+        PowerUser,
+
+        #[arg("--internal", hidden_complete)]
+        Internal,
+    }
+
+    let help = Arg::help("test");
+    assert!(!help.contains("power-user"), "{help}");
+    assert!(help.contains("internal"), "{help}");
+
+    let fish = uutils_args_complete::render(&Arg::complete(), "fish").unwrap();
+    assert!(fish.contains("-l power-user"), "{fish}");
+    assert!(!fish.contains("internal"), "{fish}");
+
+    // The documentation follows the help
+    for format in ["md", "man"] {
+        let out = uutils_args_complete::render(&Arg::complete(), format).unwrap();
+        assert!(!out.contains("power-user"), "{out}");
+        assert!(out.contains("internal"), "{out}");
+    }
+}
+
+#[test]
+fn help_and_version() {
+    #[allow(dead_code)]
+    #[derive(Arguments)]
+    enum Arg {
+        #[arg("-f", "--foo")]
+        Foo,
+    }
+
+    let fish = uutils_args_complete::render(&Arg::complete(), "fish").unwrap();
+    assert!(fish.contains("-l help"), "{fish}");
+    assert!(fish.contains("-l version"), "{fish}");
+
+    #[allow(dead_code)]
+    #[derive(Arguments)]
+    #[arguments(help_flags = ["-h", "--help"], hide_version)]
+    enum Custom {
+        #[arg("-f", "--foo")]
+        Foo,
+    }
+
+    let fish = uutils_args_complete::render(&Custom::complete(), "fish").unwrap();
+    assert!(fish.contains("-s h -l help"), "{fish}");
+    assert!(!fish.contains("version"), "{fish}");
+}
+
+#[test]
+fn optional_value_hints() {
+    #[allow(dead_code)]
+    #[derive(Value, Default)]
+    enum When {
+        #[value]
+        Always,
+        #[default]
+        #[value]
+        Auto,
+        #[value]
+        Never,
+    }
+
+    #[allow(dead_code)]
+    #[derive(Arguments)]
+    enum Arg {
+        #[arg("--color[=WHEN]")]
+        Color(Option<When>),
+        #[arg("--hyperlink[=WHEN]")]
+        Hyperlink(When),
+        #[arg("-I[WHEN]")]
+        Short(When),
+    }
+
+    let command = Arg::complete();
+    for arg in &command.args[..3] {
+        assert!(matches!(
+            &arg.value,
+            Some(ValueHint::Strings(s)) if s == &["always", "auto", "never"]
+        ));
+    }
+
+    let fish = uutils_args_complete::render(&command, "fish").unwrap();
+    assert!(
+        fish.contains("-l color -d '' -f -a \"always auto never\""),
+        "{fish}"
+    );
+    let zsh = uutils_args_complete::render(&command, "zsh").unwrap();
+    assert!(
+        zsh.contains("--color=-[]::WHEN:(always auto never)"),
+        "{zsh}"
+    );
+}
+
+#[test]
+fn aliases() {
+    #[allow(dead_code)]
+    #[derive(Arguments)]
+    enum Arg {
+        #[arg("-w N", "--width=N", alias = "--cols=N")]
+        Width(u32),
+    }
+
+    let fish = uutils_args_complete::render(&Arg::complete(), "fish").unwrap();
+    assert!(fish.contains("-l width"), "{fish}");
+    assert!(!fish.contains("cols"), "{fish}");
+}
+
+#[test]
+fn value_descriptions() {
+    #[allow(dead_code)]
+    #[derive(Value)]
+    enum When {
+        /// Always use colors
+        #[value]
+        Always,
+        /// Never use colors
+        ///
+        /// Not part of the description
+        #[value("never", "none")]
+        Never,
+        #[value]
+        Auto,
+    }
+
+    #[allow(dead_code)]
+    #[derive(Arguments)]
+    enum Arg {
+        #[arg("--color=WHEN")]
+        Color(When),
+    }
+
+    let command = Arg::complete();
+    assert!(matches!(
+        &command.args[0].value,
+        Some(ValueHint::DescribedStrings(s)) if s == &[
+            ("always".into(), "Always use colors".into()),
+            ("never".into(), "Never use colors".into()),
+            ("none".into(), "Never use colors".into()),
+            ("auto".into(), String::new()),
+        ]
+    ));
+
+    let fish = uutils_args_complete::render(&command, "fish").unwrap();
+    assert!(
+        fish.contains("-a \"always\\t'Always use colors' never\\t'Never use colors'"),
+        "{fish}"
+    );
+    let zsh = uutils_args_complete::render(&command, "zsh").unwrap();
+    assert!(zsh.contains("((always\\:\"Always use colors\""), "{zsh}");
+}
+
+#[test]
+fn value_descriptions_escaped() {
+    #[allow(dead_code)]
+    #[derive(Value)]
+    enum When {
+        /// Don't use "colors"
+        #[value]
+        Never,
+    }
+
+    #[allow(dead_code)]
+    #[derive(Arguments)]
+    enum Arg {
+        /// Don't [ever] use colors
+        #[arg("--color=WHEN")]
+        Color(When),
+    }
+
+    let command = Arg::complete();
+    let fish = uutils_args_complete::render(&command, "fish").unwrap();
+    assert!(fish.contains("-d 'Don\\'t [ever] use colors'"), "{fish}");
+    assert!(
+        fish.contains("-a \"never\\t'Don\\\\'t use \\\"colors\\\"'\""),
+        "{fish}"
+    );
+    let zsh = uutils_args_complete::render(&command, "zsh").unwrap();
+    assert!(
+        zsh.contains("'--color=[Don'\\''t \\[ever\\] use colors]:WHEN:((never\\:\"Don'\\''t use \\\"colors\\\"\"))'"),
+        "{zsh}"
+    );
+    let nu = uutils_args_complete::render(&command, "nu").unwrap();
+    assert!(
+        nu.contains("{value: \"never\", description: \"Don't use \\\"colors\\\"\"}"),
+        "{nu}"
+    );
+}
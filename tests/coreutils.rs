@@ -19,6 +19,9 @@ mod cksum;
 #[path = "coreutils/dd.rs"]
 mod dd;
 
+#[path = "coreutils/du.rs"]
+mod du;
+
 #[path = "coreutils/echo.rs"]
 mod echo;
 
@@ -36,3 +39,6 @@ mod tail;
 
 #[path = "coreutils/shuf.rs"]
 mod shuf;
+
+#[path = "coreutils/uniq.rs"]
+mod uniq;
@@ -2,7 +2,7 @@ use std::ffi::OsString;
 
 use uutils_args::{
     positional::{Opt, Unpack},
-    Arguments, Options,
+    Arguments, Error, Options,
 };
 
 #[derive(Clone, Arguments)]
@@ -44,7 +44,7 @@ impl Options<Arg> for Settings {
     }
 }
 
-fn parse<I>(args: I) -> Result<(Settings, Option<OsString>), uutils_args::Error>
+fn parse<I>(args: I) -> Result<(Settings, Option<OsString>), Error>
 where
     I: IntoIterator,
     I::Item: Into<OsString>,
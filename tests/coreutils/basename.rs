@@ -2,7 +2,7 @@ use std::ffi::OsString;
 
 use uutils_args::{
     positional::{Many1, Unpack},
-    Arguments, Options,
+    Arguments, Error, ErrorKind, Options,
 };
 
 #[derive(Clone, Arguments)]
@@ -38,7 +38,7 @@ impl Options<Arg> for Settings {
     }
 }
 
-fn parse(args: &[&str]) -> Result<Settings, uutils_args::Error> {
+fn parse(args: &[&str]) -> Result<Settings, Error> {
     let (mut settings, operands) = Settings::default().parse(args)?;
 
     if settings.multiple {
@@ -86,3 +86,36 @@ fn option_like_names() {
     assert_eq!(settings.names, vec!["-a", "-z", "--suffix=SUFFIX"]);
     assert_eq!(settings.suffix, "");
 }
+
+#[test]
+fn double_dash_is_not_a_name() {
+    let settings = parse(&["basename", "-a", "--", "--foo"]).unwrap();
+    assert_eq!(settings.names, vec!["--foo"]);
+
+    // Only the first -- separates the options from the names
+    let settings = parse(&["basename", "-a", "--", "--", "--foo"]).unwrap();
+    assert_eq!(settings.names, vec!["--", "--foo"]);
+
+    let settings = parse(&["basename", "--", "--foo", "--"]).unwrap();
+    assert_eq!(settings.names, vec!["--foo"]);
+    assert_eq!(settings.suffix, "--");
+}
+
+#[test]
+fn extra_operand() {
+    let Err(err) = parse(&["basename", "foobar", "bar", "baz", "qux"]) else {
+        panic!("basename should only take two operands");
+    };
+    match err.kind {
+        ErrorKind::TooManyPositionalArguments {
+            expected,
+            got,
+            first_extra,
+        } => {
+            assert_eq!(expected, 2);
+            assert_eq!(got, 4);
+            assert_eq!(first_extra, "baz");
+        }
+        _ => panic!("wrong error kind"),
+    }
+}
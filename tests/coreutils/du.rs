@@ -0,0 +1,109 @@
+use std::ffi::OsString;
+
+use uutils_args::{Arguments, BlockSize, ErrorKind, Options};
+
+#[derive(Arguments)]
+enum Arg {
+    /// Print apparent sizes rather than device usage
+    #[arg("--apparent-size")]
+    ApparentSize,
+
+    /// Scale sizes by SIZE before printing them
+    #[arg("-B[SIZE]", "--block-size[=SIZE]")]
+    #[arg("-k", value = BlockSize::Fixed(1024))]
+    #[arg("-m", value = BlockSize::Fixed(1024 * 1024))]
+    #[arg("-h", "--human-readable", value = BlockSize::HumanReadable)]
+    #[arg("--si", value = BlockSize::Si)]
+    BlockSize(BlockSize),
+
+    /// Equivalent to '--apparent-size --block-size=1'
+    #[arg("-b", "--bytes")]
+    Bytes,
+
+    /// Produce a grand total
+    #[arg("-c", "--total")]
+    Total,
+}
+
+#[derive(Default)]
+struct Settings {
+    apparent_size: bool,
+    block_size: BlockSize,
+    total: bool,
+}
+
+impl Options<Arg> for Settings {
+    fn apply(&mut self, arg: Arg) {
+        match arg {
+            Arg::ApparentSize => self.apparent_size = true,
+            Arg::BlockSize(size) => self.block_size = size,
+            Arg::Bytes => {
+                self.apparent_size = true;
+                self.block_size = BlockSize::Fixed(1);
+            }
+            Arg::Total => self.total = true,
+        }
+    }
+}
+
+#[test]
+fn default() {
+    let (s, operands) = Settings::default().parse(["du", "dir"]).unwrap();
+    assert!(!s.apparent_size);
+    assert_eq!(s.block_size, BlockSize::Fixed(1024));
+    assert!(!s.total);
+    assert_eq!(operands, [OsString::from("dir")]);
+}
+
+#[test]
+fn block_size() {
+    let block_size = |args: &[&str]| Settings::default().parse(args).unwrap().0.block_size;
+
+    assert_eq!(block_size(&["du", "-B512"]), BlockSize::Fixed(512));
+    assert_eq!(block_size(&["du", "-B1K"]), BlockSize::Fixed(1024));
+    assert_eq!(
+        block_size(&["du", "--block-size=1KB"]),
+        BlockSize::Fixed(1000)
+    );
+    assert_eq!(
+        block_size(&["du", "--block-size=KiB"]),
+        BlockSize::Fixed(1024)
+    );
+    assert_eq!(
+        block_size(&["du", "--block-size=1M"]),
+        BlockSize::Fixed(1024 * 1024)
+    );
+    assert_eq!(
+        block_size(&["du", "--block-size=human-readable"]),
+        BlockSize::HumanReadable
+    );
+    assert_eq!(block_size(&["du", "--block-size=si"]), BlockSize::Si);
+
+    // Without a value, the default is used
+    assert_eq!(block_size(&["du", "-m", "-B"]), BlockSize::Fixed(1024));
+    assert_eq!(
+        block_size(&["du", "-m", "--block-size"]),
+        BlockSize::Fixed(1024)
+    );
+
+    for size in ["0", "1X", "-1"] {
+        let Err(err) = Settings::default().parse(["du", &format!("--block-size={size}")]) else {
+            panic!("block size {size} should be invalid");
+        };
+        assert!(matches!(err.kind, ErrorKind::ParsingFailed { .. }));
+    }
+}
+
+#[test]
+fn last_wins() {
+    let (s, _) = Settings::default().parse(["du", "-bkm"]).unwrap();
+    assert!(s.apparent_size);
+    assert_eq!(s.block_size, BlockSize::Fixed(1024 * 1024));
+
+    let (s, _) = Settings::default().parse(["du", "-kb"]).unwrap();
+    assert_eq!(s.block_size, BlockSize::Fixed(1));
+
+    let (s, _) = Settings::default().parse(["du", "-hc", "--si"]).unwrap();
+    assert!(s.total);
+    assert_eq!(s.block_size, BlockSize::Si);
+}
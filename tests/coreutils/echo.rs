@@ -17,12 +17,20 @@ enum Arg {
     DisableEscape,
 }
 
-#[derive(Default)]
 struct Settings {
     trailing_newline: bool,
     escape: bool,
 }
 
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            trailing_newline: true,
+            escape: false,
+        }
+    }
+}
+
 impl Options<Arg> for Settings {
     fn apply(&mut self, arg: Arg) {
         match arg {
@@ -37,7 +45,6 @@ impl Options<Arg> for Settings {
 // support explicitly.
 
 #[test]
-#[ignore = "needs to be fixed after positional argument refactor"]
 fn double_hyphen() {
     let (_, operands) = Settings::default().parse(["echo", "--"]).unwrap();
     assert_eq!(operands, vec![OsString::from("--")]);
@@ -47,8 +54,110 @@ fn double_hyphen() {
 }
 
 #[test]
-#[ignore]
 fn nonexistent_options_are_values() {
     let (_, operands) = Settings::default().parse(["echo", "-f"]).unwrap();
     assert_eq!(operands, vec![OsString::from("-f")]);
 }
+
+#[test]
+fn double_hyphen_before_operand() {
+    let (s, operands) = Settings::default().parse(["echo", "--", "foo"]).unwrap();
+    assert!(s.trailing_newline);
+    assert_eq!(operands, vec![OsString::from("--"), OsString::from("foo")]);
+}
+
+#[test]
+fn multiple_options() {
+    let (s, operands) = Settings::default()
+        .parse(["echo", "-n", "-e", "foo"])
+        .unwrap();
+    assert!(!s.trailing_newline);
+    assert!(s.escape);
+    assert_eq!(operands, vec![OsString::from("foo")]);
+
+    let (s, operands) = Settings::default().parse(["echo", "-ne", "foo"]).unwrap();
+    assert!(!s.trailing_newline);
+    assert!(s.escape);
+    assert_eq!(operands, vec![OsString::from("foo")]);
+}
+
+#[test]
+fn partially_known_options_are_values() {
+    // Only "n" and "e" are known, so the whole argument is an operand.
+    let (s, operands) = Settings::default().parse(["echo", "-nxe"]).unwrap();
+    assert!(s.trailing_newline);
+    assert!(!s.escape);
+    assert_eq!(operands, vec![OsString::from("-nxe")]);
+}
+
+#[test]
+fn no_options_after_first_operand() {
+    let (s, operands) = Settings::default()
+        .parse(["echo", "-e", "foo", "-n", "-", "bar"])
+        .unwrap();
+    assert!(s.trailing_newline);
+    assert!(s.escape);
+    assert_eq!(
+        operands,
+        vec![
+            OsString::from("foo"),
+            OsString::from("-n"),
+            OsString::from("-"),
+            OsString::from("bar"),
+        ]
+    );
+
+    let (s, operands) = Settings::default().parse(["echo", "-", "-n"]).unwrap();
+    assert!(s.trailing_newline);
+    assert_eq!(operands, vec![OsString::from("-"), OsString::from("-n")]);
+}
+
+#[test]
+fn unknown_option_after_known_options() {
+    let (s, operands) = Settings::default()
+        .parse(["echo", "-n", "-x", "-e"])
+        .unwrap();
+    assert!(!s.trailing_newline);
+    assert!(!s.escape);
+    assert_eq!(operands, vec![OsString::from("-x"), OsString::from("-e")]);
+}
+
+#[test]
+fn repeated_options() {
+    let (s, operands) = Settings::default()
+        .parse(["echo", "-nn", "-eE", "foo"])
+        .unwrap();
+    assert!(!s.trailing_newline);
+    assert!(!s.escape);
+    assert_eq!(operands, vec![OsString::from("foo")]);
+}
+
+#[test]
+fn help_and_version_are_values() {
+    // echo does not have --help and --version
+    for arg in ["--help", "--version", "--he", "-h", "-V"] {
+        let (_, operands) = Settings::default().parse(["echo", arg]).unwrap();
+        assert_eq!(operands, vec![OsString::from(arg)]);
+    }
+
+    let (s, operands) = Settings::default().parse(["echo", "-n", "--help"]).unwrap();
+    assert!(!s.trailing_newline);
+    assert_eq!(operands, vec![OsString::from("--help")]);
+}
+
+#[test]
+fn options_with_attached_text_are_values() {
+    for arg in ["-n=foo", "-nfoo", "--n", "--no-newline", ""] {
+        let (s, operands) = Settings::default().parse(["echo", arg]).unwrap();
+        assert!(s.trailing_newline);
+        assert_eq!(operands, vec![OsString::from(arg)]);
+    }
+}
+
+#[test]
+fn only_options() {
+    let (s, operands) = Settings::default().parse(["echo", "-n", "-e"]).unwrap();
+    assert!(!s.trailing_newline);
+    assert!(s.escape);
+    assert!(operands.is_empty());
+}
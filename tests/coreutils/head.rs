@@ -1,6 +1,6 @@
 use std::{ffi::OsString, path::PathBuf};
 
-use uutils_args::{Arguments, Options, Value};
+use uutils_args::{Arguments, Options, Size, SizeError, Value};
 
 // This format is way to specific to implement using a library. Basically, any
 // deviation should be return `None` to indicate that we're not using the
@@ -111,6 +111,14 @@ enum SigNum {
 
 impl Value for SigNum {
     fn from_value(value: &std::ffi::OsStr) -> uutils_args::ValueResult<Self> {
+        Self::from_value_named(value, "-n")
+    }
+
+    fn from_value_named(value: &std::ffi::OsStr, option: &str) -> uutils_args::ValueResult<Self> {
+        let unit = match option {
+            "-c" | "--bytes" => "bytes",
+            _ => "lines",
+        };
         let s = String::from_value(value)?;
         let mut rest: &str = &s;
 
@@ -124,41 +132,12 @@ impl Value for SigNum {
             Self::Negative
         };
 
-        // Get the number from the front of the string
-        let end_num = rest
-            .find(|c: char| !c.is_ascii_digit())
-            .unwrap_or(rest.len());
-        let num = rest[..end_num].parse().unwrap_or(1);
-        rest = &rest[end_num..];
-
-        // Determine the multiplier
-        // We're being a bit overly defensive here. I'm assuming it will
-        // be optimized away.
-        let multiplier: Option<u64> = match rest {
-            "" => Some(1),
-            "b" => Some(512),
-            "K" | "KiB" => Some(1024),
-            "M" | "MiB" => 1024_u64.checked_pow(2),
-            "G" | "GiB" => 1024_u64.checked_pow(3),
-            "T" | "TiB" => 1024_u64.checked_pow(4),
-            "P" | "PiB" => 1024_u64.checked_pow(5),
-            "E" | "EiB" => 1024_u64.checked_pow(6),
-            "Z" | "ZiB" => 1024_u64.checked_pow(7),
-            "Y" | "YiB" => 1024_u64.checked_pow(8),
-            "KB" => Some(1000),
-            "MB" => 1000_u64.checked_pow(2),
-            "GB" => 1000_u64.checked_pow(3),
-            "TB" => 1000_u64.checked_pow(4),
-            "PB" => 1000_u64.checked_pow(5),
-            "EB" => 1000_u64.checked_pow(6),
-            "ZB" => 1000_u64.checked_pow(7),
-            "YB" => 1000_u64.checked_pow(8),
-            _ => return Err(format!("Invalid number of lines: {s}").into()),
-        };
-
-        let number = match multiplier.and_then(|m| m.checked_mul(num)) {
-            Some(number) => number,
-            None => return Err("Value too large for defined data type".into()),
+        let number = match Size::parse(rest) {
+            Ok(Size(number)) => number,
+            Err(SizeError::Invalid(_)) => {
+                return Err(format!("invalid number of {unit}: '{s}'").into())
+            }
+            Err(err) => return Err(err.into()),
         };
 
         Ok(sign(number))
@@ -267,3 +246,16 @@ fn signum() {
 
     assert!(parse_head(["head", "-n", "20invalid_suffix"]).is_err());
 }
+
+#[test]
+fn invalid_number_mentions_unit() {
+    let Err(err) = parse_head(["head", "-n", "20x"]) else {
+        panic!("expected an error");
+    };
+    assert!(err.to_string().contains("invalid number of lines: '20x'"));
+
+    let Err(err) = parse_head(["head", "--bytes=20x"]) else {
+        panic!("expected an error");
+    };
+    assert!(err.to_string().contains("invalid number of bytes: '20x'"));
+}
@@ -254,7 +254,7 @@ enum Arg {
     // === Quoting style ===
     #[arg("--quoting-style=STYLE")]
     #[arg("-N", "--literal", value = QuotingStyle::Literal)]
-    #[arg("-h", "--escape", value = QuotingStyle::Escape)]
+    #[arg("-b", "--escape", value = QuotingStyle::Escape)]
     #[arg("-Q", "--quote-name", value = todo!())]
     QuotingStyle(QuotingStyle),
 
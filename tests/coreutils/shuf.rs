@@ -1,7 +1,7 @@
 use std::{ffi::OsString, path::PathBuf};
 use uutils_args::{
     positional::{Many0, Opt, Unpack},
-    Arguments, Options,
+    Arguments, Error, ErrorKind, Options,
 };
 
 #[derive(Clone, Arguments)]
@@ -31,7 +31,7 @@ impl Options<Arg> for Settings {
     }
 }
 
-fn parse(args: &[&str]) -> Result<Settings, uutils_args::Error> {
+fn parse(args: &[&str]) -> Result<Settings, Error> {
     let (mut settings, operands) = Settings::default().parse(args)?;
 
     if settings.echo {
@@ -43,6 +43,22 @@ fn parse(args: &[&str]) -> Result<Settings, uutils_args::Error> {
     Ok(settings)
 }
 
+#[track_caller]
+fn assert_too_many(result: Result<Settings, Error>, expected_got: usize) {
+    match result.unwrap_err().kind {
+        ErrorKind::TooManyPositionalArguments {
+            expected,
+            got,
+            first_extra,
+        } => {
+            assert_eq!(expected, 1);
+            assert_eq!(got, expected_got);
+            assert_eq!(first_extra, "otherfile");
+        }
+        _ => panic!("wrong error kind"),
+    }
+}
+
 #[test]
 fn noarg_is_file() {
     let settings = parse(&["shuf"]).unwrap();
@@ -63,14 +79,12 @@ fn file_takes_one_arg() {
 
 #[test]
 fn file_refuses_two_files() {
-    // FIXME: Check detected error
-    assert!(parse(&["shuf", "myfile", "otherfile"]).is_err());
+    assert_too_many(parse(&["shuf", "myfile", "otherfile"]), 2);
 }
 
 #[test]
 fn file_refuses_three_files() {
-    // FIXME: Check detected error
-    assert!(parse(&["shuf", "myfile", "otherfile", "morefile"]).is_err());
+    assert_too_many(parse(&["shuf", "myfile", "otherfile", "morefile"]), 3);
 }
 
 #[test]
@@ -87,14 +101,9 @@ fn noarg_is_file_zero() {
 
 #[test]
 fn the_help() {
-    let settings = parse(&["shuf", "--help"]).unwrap();
-    assert_eq!(
-        settings,
-        Settings {
-            zero: false,
-            ..Settings::default()
-        }
-    );
+    let err = parse(&["shuf", "--help"]).unwrap_err();
+    assert_eq!(err.exit_code, 0);
+    assert!(matches!(err.kind, ErrorKind::HelpRequested(_)));
 }
 
 #[test]
@@ -125,14 +134,12 @@ fn file_zero_postfix_takes_one_arg() {
 
 #[test]
 fn file_zero_refuses_two_files() {
-    // FIXME: Check detected error
-    assert!(parse(&["shuf", "-z", "myfile", "otherfile"]).is_err());
+    assert_too_many(parse(&["shuf", "-z", "myfile", "otherfile"]), 2);
 }
 
 #[test]
 fn file_zero_refuses_three_files() {
-    // FIXME: Check detected error
-    assert!(parse(&["shuf", "-z", "myfile", "otherfile", "morefile"]).is_err());
+    assert_too_many(parse(&["shuf", "-z", "myfile", "otherfile", "morefile"]), 3);
 }
 
 #[test]
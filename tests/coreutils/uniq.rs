@@ -1,40 +1,40 @@
-use uutils_args::{Arguments, Initial, Options, Value};
+use uutils_args::{Arguments, Options, Value};
 
 // TODO: Deprecated syntax
 #[derive(Arguments)]
 enum Arg {
-    #[option("-f N", "--skip-fields=n")]
+    #[arg("-f N", "--skip-fields=N")]
     SkipFields(usize),
 
-    #[option("-s N", "--skip-chars=N")]
+    #[arg("-s N", "--skip-chars=N")]
     SkipChars(usize),
 
-    #[option("-c", "--count")]
+    #[arg("-c", "--count")]
     Count,
 
-    #[option("-i", "--ignore-case")]
+    #[arg("-i", "--ignore-case")]
     IgnoreCase,
 
-    #[option("-d", "--repeated")]
+    #[arg("-d", "--repeated")]
     Repeated,
 
-    #[option("-D", "--all-repeated[=delimit-method]")]
+    #[arg("-D", "--all-repeated[=delimit-method]")]
     AllRepeated(Delimiters),
 
-    #[option("--group[=delimit-method]", default=Delimiters::Separate)]
+    #[arg("--group[=delimit-method]", value = Delimiters::Separate)]
     Group(Delimiters),
 
-    #[option("-u", "--unique")]
+    #[arg("-u", "--unique")]
     Unique,
 
-    #[option("-w N", "--check-chars=N")]
+    #[arg("-w N", "--check-chars=N")]
     CheckChars(usize),
 
-    #[option("-z", "--zero-terminated")]
+    #[arg("-z", "--zero-terminated")]
     ZeroTerminated,
 }
 
-#[derive(Value, Default)]
+#[derive(Value, Default, Debug, PartialEq, Eq)]
 enum Delimiters {
     #[default]
     #[value("none")]
@@ -103,3 +103,32 @@ impl Options<Arg> for Settings {
         }
     }
 }
+
+#[test]
+fn skip_and_check() {
+    let (settings, operands) = Settings::default()
+        .parse(["uniq", "-f", "1", "--skip-chars=2", "-w3", "input"])
+        .unwrap();
+    assert_eq!(settings.skip_fields, Some(1));
+    assert_eq!(settings.slice_start, Some(2));
+    assert_eq!(settings.slice_stop, Some(3));
+    assert_eq!(operands, vec!["input"]);
+}
+
+#[test]
+fn delimiters() {
+    let (settings, _) = Settings::default().parse(["uniq", "-D"]).unwrap();
+    assert!(settings.repeats_only);
+    assert!(settings.all_repeated);
+    assert_eq!(settings.delimiters, Delimiters::None);
+
+    let (settings, _) = Settings::default()
+        .parse(["uniq", "--all-repeated=prepend"])
+        .unwrap();
+    assert_eq!(settings.delimiters, Delimiters::Prepend);
+
+    let (settings, _) = Settings::default().parse(["uniq", "--group"]).unwrap();
+    assert!(!settings.repeats_only);
+    assert!(settings.all_repeated);
+    assert_eq!(settings.delimiters, Delimiters::Separate);
+}
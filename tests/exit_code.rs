@@ -11,3 +11,77 @@ fn one_flag() {
 
     assert_eq!(Arg::EXIT_CODE, 4);
 }
+
+#[test]
+fn usage_and_error_exit_codes() {
+    use std::io::{self, Write};
+    use uutils_args::{
+        positional::{Many1, Unpack},
+        ErrorKind, Options,
+    };
+
+    #[derive(Arguments)]
+    #[arguments(usage_exit_code = 2, error_exit_code = 3)]
+    enum Arg {
+        #[arg("-f", "--foo")]
+        Foo,
+    }
+
+    #[derive(Debug)]
+    struct Settings;
+
+    impl Options<Arg> for Settings {
+        fn apply(&mut self, Arg::Foo: Arg) {}
+    }
+
+    /// A writer that always fails, to cause an I/O error
+    struct Broken;
+
+    impl Write for Broken {
+        fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+            Err(io::ErrorKind::BrokenPipe.into())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    assert_eq!(Arg::EXIT_CODE, 2);
+    assert_eq!(Arg::ERROR_EXIT_CODE, 3);
+
+    let err = Settings.parse(["test", "--bar"]).unwrap_err();
+    assert!(err.kind.is_usage_error());
+    assert_eq!(err.exit_code, 2);
+
+    let err = Settings
+        .parse_capturing(["test", "--help"], &mut Broken)
+        .unwrap_err();
+    assert!(matches!(err.kind, ErrorKind::IoError(_)));
+    assert!(!err.kind.is_usage_error());
+    assert_eq!(err.exit_code, 3);
+
+    // Errors of operands are usage errors with exit code 1, unless they are
+    // unpacked for the arguments they belong to
+    let err = Many1("FILE").unpack(Vec::<&str>::new()).unwrap_err();
+    assert!(matches!(err.kind, ErrorKind::MissingPositionalArguments(_)));
+    assert_eq!(err.exit_code, 1);
+    let err = Many1("FILE")
+        .unpack_for::<Arg, &str>(Vec::new())
+        .unwrap_err();
+    assert!(matches!(err.kind, ErrorKind::MissingPositionalArguments(_)));
+    assert_eq!(err.exit_code, 2);
+}
+
+#[test]
+fn exit_code_sets_both() {
+    #[derive(Arguments)]
+    #[arguments(exit_code = 4)]
+    enum Arg {
+        #[arg("-f", "--foo")]
+        Foo,
+    }
+
+    assert_eq!(Arg::EXIT_CODE, 4);
+    assert_eq!(Arg::ERROR_EXIT_CODE, 4);
+}
@@ -1,4 +1,4 @@
-use uutils_args::{Arguments, Options};
+use uutils_args::{Arguments, ErrorKind, Options};
 
 #[test]
 fn one_flag() {
@@ -456,6 +456,204 @@ fn infer_long_args() {
     assert!(Settings::default().parse(["test", "--a"]).is_err());
 }
 
+#[test]
+fn infer_long_args_prefix_of_another() {
+    // The longer options are defined first, so that the exact match is not
+    // the first option that starts with the input.
+    #[derive(Arguments, Debug, PartialEq, Eq)]
+    enum Arg {
+        #[arg("--all-files")]
+        AllFiles,
+        #[arg("--all-dirs")]
+        AllDirs,
+        #[arg("--all")]
+        All,
+    }
+
+    #[derive(Default)]
+    struct Settings {
+        args: Vec<Arg>,
+    }
+
+    impl Options<Arg> for Settings {
+        fn apply(&mut self, arg: Arg) {
+            self.args.push(arg);
+        }
+    }
+
+    let parse = |arg| {
+        Settings::default()
+            .parse(["test", arg])
+            .map(|(s, _)| s.args)
+    };
+
+    assert_eq!(parse("--all").unwrap(), [Arg::All]);
+    assert_eq!(parse("--all-f").unwrap(), [Arg::AllFiles]);
+    assert_eq!(parse("--all-d").unwrap(), [Arg::AllDirs]);
+
+    // "all" does not start with "all-", so it is not a candidate
+    let Err(err) = parse("--all-") else {
+        panic!("--all- should be ambiguous");
+    };
+    let ErrorKind::AmbiguousOption { option, candidates } = err.kind else {
+        panic!("expected an ambiguous option, got {err}");
+    };
+    assert_eq!(option, "all-");
+    assert_eq!(candidates, ["all-dirs", "all-files"]);
+
+    let Err(err) = parse("--al") else {
+        panic!("--al should be ambiguous");
+    };
+    let ErrorKind::AmbiguousOption { candidates, .. } = err.kind else {
+        panic!("expected an ambiguous option, got {err}");
+    };
+    assert_eq!(candidates, ["all", "all-dirs", "all-files"]);
+}
+
+#[test]
+fn infer_long_args_case_sensitive_by_default() {
+    #[derive(Arguments)]
+    enum Arg {
+        #[arg("--all")]
+        All,
+    }
+
+    struct Settings;
+
+    impl Options<Arg> for Settings {
+        fn apply(&mut self, Arg::All: Arg) {}
+    }
+
+    assert!(Settings.parse(["test", "--all"]).is_ok());
+    assert!(Settings.parse(["test", "--ALL"]).is_err());
+    assert!(Settings.parse(["test", "--Al"]).is_err());
+}
+
+#[test]
+fn infer_long_args_case_insensitive() {
+    #[derive(Arguments)]
+    #[arguments(case_insensitive_long)]
+    enum Arg {
+        #[arg("--all")]
+        All,
+        #[arg("--almost-all")]
+        AlmostAll,
+        #[arg("--author")]
+        Author,
+    }
+
+    #[derive(Default)]
+    struct Settings {
+        all: bool,
+        almost_all: bool,
+        author: bool,
+    }
+
+    impl Options<Arg> for Settings {
+        fn apply(&mut self, arg: Arg) {
+            match arg {
+                Arg::All => self.all = true,
+                Arg::AlmostAll => self.almost_all = true,
+                Arg::Author => self.author = true,
+            }
+        }
+    }
+
+    assert!(Settings::default().parse(["test", "--ALL"]).unwrap().0.all);
+    assert!(
+        Settings::default()
+            .parse(["test", "--ALM"])
+            .unwrap()
+            .0
+            .almost_all
+    );
+    assert!(
+        Settings::default()
+            .parse(["test", "--Au"])
+            .unwrap()
+            .0
+            .author
+    );
+    assert!(Settings::default().parse(["test", "--A"]).is_err());
+}
+
+#[test]
+fn suggestion_threshold() {
+    #[derive(Arguments)]
+    enum Default {
+        #[arg("--color")]
+        Color,
+        #[arg("--colors")]
+        Colors,
+        #[arg("--colour")]
+        Colour,
+        #[arg("--column")]
+        Column,
+    }
+
+    #[derive(Arguments)]
+    #[arguments(suggestion_threshold = 0.9)]
+    enum Strict {
+        #[arg("--color")]
+        Color,
+        #[arg("--colors")]
+        Colors,
+        #[arg("--colour")]
+        Colour,
+        #[arg("--column")]
+        Column,
+    }
+
+    #[derive(Debug)]
+    struct Settings;
+
+    impl Options<Default> for Settings {
+        fn apply(&mut self, _: Default) {}
+    }
+
+    impl Options<Strict> for Settings {
+        fn apply(&mut self, _: Strict) {}
+    }
+
+    let suggestions = |kind| match kind {
+        ErrorKind::UnexpectedOption(_, suggestions) => suggestions,
+        _ => panic!("wrong error kind"),
+    };
+
+    let err = Options::<Default>::parse(Settings, ["test", "--colr"]).unwrap_err();
+    assert_eq!(suggestions(err.kind), ["--color", "--colors", "--colour"]);
+
+    let err = Options::<Strict>::parse(Settings, ["test", "--colr"]).unwrap_err();
+    assert_eq!(suggestions(err.kind), ["--color"]);
+}
+
+#[test]
+fn unknown_short_suggestions() {
+    #[derive(Arguments)]
+    enum Arg {
+        #[arg("-x")]
+        X,
+        #[arg("--color")]
+        Color,
+    }
+
+    #[derive(Debug)]
+    struct Settings;
+
+    impl Options<Arg> for Settings {
+        fn apply(&mut self, _: Arg) {}
+    }
+
+    let suggestions = |args: &[&str]| match Settings.parse(args).unwrap_err().kind {
+        ErrorKind::UnexpectedOption(_, suggestions) => suggestions,
+        _ => panic!("wrong error kind"),
+    };
+
+    assert_eq!(suggestions(&["test", "-X"]), ["-x"]);
+    assert_eq!(suggestions(&["test", "-color"]), ["--color"]);
+    assert_eq!(suggestions(&["test", "-q"]), [""; 0]);
+}
+
 #[test]
 fn enum_flag() {
     #[derive(Default, PartialEq, Eq, Debug)]
@@ -504,3 +702,306 @@ fn enum_flag() {
         SomeEnum::Baz,
     );
 }
+
+#[test]
+fn deprecated_flag() {
+    #[derive(Arguments)]
+    enum Arg {
+        #[arg("-f", "--foo")]
+        #[arg("--old-foo", "-F", deprecated = "use --foo instead")]
+        Foo,
+    }
+
+    #[derive(Default)]
+    struct Settings {
+        foo: u8,
+    }
+
+    impl Options<Arg> for Settings {
+        fn apply(&mut self, Arg::Foo: Arg) {
+            self.foo += 1;
+        }
+    }
+
+    assert_eq!(
+        Settings::default()
+            .parse(["test", "--old-foo", "-F", "--foo"])
+            .unwrap()
+            .0
+            .foo,
+        3
+    );
+}
+
+#[test]
+fn flags_table() {
+    use uutils_args::flags::{Flag, FlagInfo, FlagValue};
+
+    #[allow(dead_code)]
+    #[derive(Arguments)]
+    enum Arg {
+        /// Do the foo
+        #[arg("-f", "--foo")]
+        Foo,
+        /// Set the width
+        #[arg("-w N", "--width=N", "--cols[=N]", value = 80)]
+        Width(u32),
+        #[arg("--secret", hidden)]
+        Secret,
+        #[arg("if=FILE")]
+        Input(String),
+    }
+
+    assert_eq!(
+        Arg::flags(),
+        &[
+            FlagInfo {
+                short: &[Flag {
+                    flag: "f",
+                    value: FlagValue::No,
+                    alias: false,
+                }],
+                long: &[Flag {
+                    flag: "foo",
+                    value: FlagValue::No,
+                    alias: false,
+                }],
+                help: "Do the foo",
+                hidden_help: false,
+                hidden_complete: false,
+            },
+            FlagInfo {
+                short: &[Flag {
+                    flag: "w",
+                    value: FlagValue::Required("N"),
+                    alias: false,
+                }],
+                long: &[
+                    Flag {
+                        flag: "width",
+                        value: FlagValue::Required("N"),
+                        alias: false,
+                    },
+                    Flag {
+                        flag: "cols",
+                        value: FlagValue::Optional("N"),
+                        alias: false,
+                    },
+                ],
+                help: "Set the width",
+                hidden_help: false,
+                hidden_complete: false,
+            },
+            FlagInfo {
+                short: &[],
+                long: &[Flag {
+                    flag: "secret",
+                    value: FlagValue::No,
+                    alias: false,
+                }],
+                help: "",
+                hidden_help: true,
+                hidden_complete: true,
+            },
+        ]
+    );
+}
+
+#[test]
+fn aliases() {
+    use uutils_args::flags::{Flag, FlagValue};
+
+    #[derive(Arguments)]
+    enum Arg {
+        /// Set the width
+        #[arg("-w N", "--width=N", alias = "--cols=N", alias = "-c N")]
+        Width(u32),
+    }
+
+    #[derive(Default)]
+    struct Settings {
+        width: u32,
+    }
+
+    impl Options<Arg> for Settings {
+        fn apply(&mut self, Arg::Width(w): Arg) {
+            self.width = w;
+        }
+    }
+
+    let width = |args: &[&str]| Settings::default().parse(args).unwrap().0.width;
+    assert_eq!(width(&["test", "--width=1"]), 1);
+    assert_eq!(width(&["test", "--cols=2"]), 2);
+    assert_eq!(width(&["test", "-c", "3"]), 3);
+
+    let help = Arg::help("test");
+    assert!(help.contains("-w N, --width=N"));
+    assert!(!help.contains("--cols"));
+    assert!(!help.contains("-c N"));
+
+    // The aliases are still part of the table of flags
+    let info = Arg::flags()[0];
+    assert_eq!(
+        info.short,
+        &[
+            Flag {
+                flag: "w",
+                value: FlagValue::Required("N"),
+                alias: false,
+            },
+            Flag {
+                flag: "c",
+                value: FlagValue::Required("N"),
+                alias: true,
+            },
+        ]
+    );
+    assert_eq!(
+        info.long,
+        &[
+            Flag {
+                flag: "width",
+                value: FlagValue::Required("N"),
+                alias: false,
+            },
+            Flag {
+                flag: "cols",
+                value: FlagValue::Required("N"),
+                alias: true,
+            },
+        ]
+    );
+}
+
+#[test]
+#[cfg(feature = "gnu-errors")]
+fn gnu_error_messages() {
+    #[allow(dead_code)]
+    #[derive(Arguments)]
+    enum Arg {
+        #[arg("-w N", "--width=N")]
+        Width(u32),
+        #[arg("--all-files")]
+        AllFiles,
+        #[arg("--all-dirs")]
+        AllDirs,
+        #[arg("if=FILE")]
+        Input(String),
+    }
+
+    let message = |args: &[&str]| Arg::check(args).unwrap_err().to_string();
+
+    assert_eq!(
+        message(&["test", "-w"]),
+        "option requires an argument -- 'w'"
+    );
+    assert_eq!(
+        message(&["test", "--width"]),
+        "option '--width' requires an argument"
+    );
+    assert_eq!(message(&["test", "-x"]), "invalid option -- 'x'");
+    assert_eq!(
+        message(&["test", "--widht"]),
+        "unrecognized option '--widht'"
+    );
+    assert_eq!(message(&["test", "of=x"]), "unrecognized operand 'of'");
+    // Errors without a GNU wording keep their prefix
+    let width = message(&["test", "-w", "x"]);
+    assert!(width.starts_with("error: Invalid value 'x'"), "{width}");
+    assert_eq!(
+        message(&["test", "--all"]),
+        "option '--all' is ambiguous; possibilities: '--all-dirs' '--all-files'"
+    );
+
+    let err = uutils_args::positional::require_at_least::<&str>(&[], 2, "FILE").unwrap_err();
+    assert_eq!(err.to_string(), "missing file operand");
+}
+
+#[test]
+fn unexpected_value() {
+    #[derive(Arguments)]
+    enum Arg {
+        #[arg("-f", "--foo")]
+        Foo,
+        #[arg("-x")]
+        X,
+    }
+
+    for (args, expected_option) in [
+        (&["test", "--foo=bar"][..], "--foo"),
+        (&["test", "--fo=bar"], "--foo"),
+        (&["test", "-f=bar"], "-f"),
+        (&["test", "-xf=bar"], "-f"),
+    ] {
+        let err = Arg::check(args).unwrap_err();
+        let ErrorKind::UnexpectedValue { option, value } = err.kind else {
+            panic!("expected an unexpected value for {args:?}, got {err}");
+        };
+        assert_eq!(option, expected_option);
+        assert_eq!(value, "bar");
+    }
+
+    // An empty value is still a value
+    let err = Arg::check(["test", "--foo="]).unwrap_err();
+    assert!(matches!(err.kind, ErrorKind::UnexpectedValue { value, .. } if value.is_empty()));
+}
+
+#[test]
+fn long_options_with_punctuation() {
+    #[derive(Arguments)]
+    enum Arg {
+        #[arg("--log.level=LEVEL")]
+        LogLevel(String),
+        #[arg("--log.file[=FILE]")]
+        LogFile(Option<String>),
+        #[arg("--enable+feature")]
+        EnableFeature,
+        #[arg("--snake_case")]
+        SnakeCase,
+    }
+
+    #[derive(Default, Debug)]
+    struct Settings {
+        level: String,
+        file: Option<String>,
+        feature: bool,
+        snake: bool,
+    }
+
+    impl Options<Arg> for Settings {
+        fn apply(&mut self, arg: Arg) {
+            match arg {
+                Arg::LogLevel(level) => self.level = level,
+                Arg::LogFile(file) => self.file = file,
+                Arg::EnableFeature => self.feature = true,
+                Arg::SnakeCase => self.snake = true,
+            }
+        }
+    }
+
+    let (s, _) = Settings::default()
+        .parse([
+            "test",
+            "--log.level=debug",
+            "--enable+feature",
+            "--snake_case",
+        ])
+        .unwrap();
+    assert_eq!(s.level, "debug");
+    assert!(s.feature);
+    assert!(s.snake);
+
+    let (s, _) = Settings::default()
+        .parse(["test", "--log.l", "info", "--log.f=out.log"])
+        .unwrap();
+    assert_eq!(s.level, "info");
+    assert_eq!(s.file.as_deref(), Some("out.log"));
+
+    assert!(matches!(
+        Settings::default()
+            .parse(["test", "--log.=debug"])
+            .unwrap_err()
+            .kind,
+        ErrorKind::AmbiguousOption { .. }
+    ));
+}
@@ -0,0 +1,284 @@
+use uutils_args::{Arguments, ErrorKind, Options, ParseOutcome};
+
+#[test]
+fn default_help_and_version_lines() {
+    #[derive(Arguments)]
+    enum Arg {
+        /// Some flag
+        #[arg("-f", "--foo")]
+        Foo,
+    }
+
+    let help = Arg::help("test");
+    assert!(help.contains("Some flag"));
+    assert!(help.contains("--help"));
+    assert!(help.contains("Display this help message"));
+    assert!(help.contains("--version"));
+    assert!(help.contains("Display version information"));
+}
+
+#[test]
+fn custom_help_and_version_descriptions() {
+    #[derive(Arguments)]
+    #[arguments(
+        help_description = "show this help",
+        version_description = "show the version"
+    )]
+    enum Arg {
+        #[arg("-f", "--foo")]
+        Foo,
+    }
+
+    let help = Arg::help("test");
+    assert!(help.contains("show this help"));
+    assert!(help.contains("show the version"));
+    assert!(!help.contains("Display this help message"));
+    assert!(!help.contains("Display version information"));
+}
+
+#[test]
+fn hide_version() {
+    #[derive(Arguments)]
+    #[arguments(hide_version)]
+    enum Arg {
+        #[arg("-f", "--foo")]
+        Foo,
+    }
+
+    let help = Arg::help("test");
+    assert!(help.contains("--help"));
+    assert!(!help.contains("--version"));
+    assert!(!help.contains("Display version information"));
+}
+
+#[test]
+fn custom_name() {
+    #[derive(Arguments)]
+    enum Arg {
+        #[arg("-f", "--foo")]
+        Foo,
+    }
+
+    let version = env!("CARGO_PKG_VERSION");
+    assert_eq!(Arg::version_with_name("ls"), format!("ls {version}"));
+    assert!(Arg::help_with_name("ls", "coreutils ls").starts_with(&format!("ls {version}\n")));
+    assert!(Arg::version().ends_with(version));
+    assert!(!Arg::version().starts_with("ls "));
+}
+
+#[test]
+fn version_extra() {
+    #[derive(Arguments)]
+    #[arguments(version_extra = "(GNU coreutils)")]
+    enum Arg {
+        #[arg("-f", "--foo")]
+        Foo,
+    }
+
+    let version = env!("CARGO_PKG_VERSION");
+    assert_eq!(
+        Arg::version_with_name("ls"),
+        format!("ls (GNU coreutils) {version}")
+    );
+}
+
+#[test]
+fn help_short_circuits() {
+    use uutils_args::{lexopt::Parser, Argument};
+
+    #[derive(Arguments)]
+    enum Default {
+        #[arg("-f", "--foo")]
+        Foo,
+    }
+
+    #[derive(Arguments)]
+    #[arguments(help_short_circuits)]
+    enum ShortCircuit {
+        #[arg("-f", "--foo")]
+        Foo,
+    }
+
+    let next_default = |args: &[&str]| Default::next_arg(&mut Parser::from_args(args));
+    let next_short_circuit = |args: &[&str]| ShortCircuit::next_arg(&mut Parser::from_args(args));
+
+    assert!(next_default(&["--bar", "--help"]).is_err());
+    assert!(matches!(
+        next_short_circuit(&["--bar", "--help"]),
+        Ok(Some(Argument::Help))
+    ));
+    assert!(matches!(
+        next_short_circuit(&["-xyz", "--version"]),
+        Ok(Some(Argument::Version))
+    ));
+    assert!(matches!(
+        next_short_circuit(&["--bar", "x", "--help"]),
+        Ok(Some(Argument::Help))
+    ));
+    assert!(next_short_circuit(&["--bar", "--", "--help"]).is_err());
+    assert!(next_short_circuit(&["--bar", "x"]).is_err());
+}
+
+#[test]
+fn help_file_relative_to_source() {
+    #[derive(Arguments)]
+    #[arguments(file = "fixtures/help.md", file_base = "source")]
+    enum Arg {
+        #[arg("-f", "--foo")]
+        Foo,
+    }
+
+    let help = Arg::help("test");
+    assert!(help.contains("Summary from the fixture."));
+    assert!(help.contains("Text after the options."));
+    assert!(help.contains("See the manual (https://example.com/manual) for more."));
+}
+
+#[test]
+fn help_from_section() {
+    #[derive(Arguments)]
+    #[arguments(file = "fixtures/help.md", file_base = "source")]
+    enum Arg {
+        /// Not shown, because the section takes precedence
+        #[arg("-v", "--verbose", help_section = "verbose")]
+        Verbose,
+    }
+
+    let help = Arg::help("test");
+    assert!(
+        help.contains("Print what is being done, see the manual (https://example.com/verbose).")
+    );
+    assert!(!help.contains("Not shown"));
+    // The section is not part of the after help
+    assert_eq!(help.matches("Print what is being done").count(), 1);
+    assert_eq!(
+        Arg::flags()[0].help,
+        "Print what is being done, see the manual (https://example.com/verbose)."
+    );
+}
+
+#[test]
+fn help_and_version_requested() {
+    #[derive(Arguments)]
+    enum Arg {
+        #[arg("-f", "--foo")]
+        Foo,
+    }
+
+    let err = Arg::check(["test", "-f", "--help"]).unwrap_err();
+    assert_eq!(err.exit_code, 0);
+    let ErrorKind::HelpRequested(help) = &err.kind else {
+        panic!("expected the help to be requested");
+    };
+    assert!(help.contains("--foo"));
+    assert_eq!(err.to_string(), *help);
+
+    let err = Arg::check(["test", "--version", "-f"]).unwrap_err();
+    assert_eq!(err.exit_code, 0);
+    assert!(matches!(err.kind, ErrorKind::VersionRequested(v) if v == Arg::version()));
+}
+
+#[test]
+fn parse_capturing() {
+    #[derive(Arguments)]
+    enum Arg {
+        #[arg("-f", "--foo")]
+        Foo,
+    }
+
+    #[derive(Default, Debug)]
+    struct Settings {
+        foo: bool,
+    }
+
+    impl Options<Arg> for Settings {
+        fn apply(&mut self, Arg::Foo: Arg) {
+            self.foo = true;
+        }
+    }
+
+    let mut out = Vec::new();
+    let outcome = Settings::default()
+        .parse_capturing(["test", "--help"], &mut out)
+        .unwrap();
+    assert!(matches!(outcome, ParseOutcome::Help));
+    assert_eq!(String::from_utf8(out).unwrap(), Arg::help("test"));
+
+    let mut out = Vec::new();
+    let outcome = Settings::default()
+        .parse_capturing(["test", "--version"], &mut out)
+        .unwrap();
+    assert!(matches!(outcome, ParseOutcome::Version));
+    assert_eq!(String::from_utf8(out).unwrap(), Arg::version() + "\n");
+
+    let mut out = Vec::new();
+    let outcome = Settings::default()
+        .parse_capturing(["test", "-f", "bar"], &mut out)
+        .unwrap();
+    let ParseOutcome::Parsed(settings, operands) = outcome else {
+        panic!("expected the arguments to be parsed");
+    };
+    assert!(settings.foo);
+    assert_eq!(operands, ["bar"]);
+    assert!(out.is_empty());
+
+    let mut out = Vec::new();
+    assert!(Settings::default()
+        .parse_capturing(["test", "--bar"], &mut out)
+        .is_err());
+    assert!(out.is_empty());
+}
+
+#[test]
+fn usage_from_operands() {
+    #[derive(Arguments)]
+    #[arguments(usage_operands = ["SOURCE...", "DEST"])]
+    enum Arg {
+        #[arg("-f", "--force")]
+        Force,
+    }
+
+    #[derive(Arguments)]
+    enum Default {
+        #[arg("-f", "--force")]
+        Force,
+    }
+
+    // A help file without a usage gets the default usage as well
+    #[derive(Arguments)]
+    #[arguments(
+        file = "fixtures/no_usage.md",
+        file_base = "source",
+        usage_operands = ["SOURCE...", "DEST"]
+    )]
+    enum File {
+        #[arg("-f", "--force")]
+        Force,
+    }
+
+    assert!(Arg::help("cp").contains("Usage:\n  cp [OPTIONS] SOURCE... DEST\n"));
+    assert!(Default::help("cp").contains("Usage:\n  cp [OPTIONS] [ARGUMENTS]\n"));
+    assert!(File::help("cp").contains("Usage:\n  cp [OPTIONS] SOURCE... DEST\n"));
+}
+
+#[test]
+fn examples_and_environment_sections() {
+    #[derive(Arguments)]
+    #[arguments(file = "fixtures/help.md", file_base = "source")]
+    enum Arg {
+        #[arg("-f", "--foo")]
+        Foo,
+    }
+
+    let help = Arg::help("test");
+    let options = help.find("Options:").unwrap();
+    let examples = help
+        .find("\nExamples:\n  Show each step:\n\n  fixture --verbose\n")
+        .unwrap();
+    let environment = help
+        .find("\nEnvironment:\n  `TZ` sets the time zone, see tzset (https://example.com/tzset).\n")
+        .unwrap();
+    let after = help.find("Text after the options.").unwrap();
+    assert!(options < examples && examples < environment && environment < after);
+    assert!(!help.contains("```"));
+}
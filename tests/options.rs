@@ -1,6 +1,12 @@
-use std::ffi::OsStr;
+use std::{
+    ffi::{OsStr, OsString},
+    path::PathBuf,
+};
 
-use uutils_args::{Arguments, Options, Value, ValueResult};
+use uutils_args::{
+    Arguments, Bytes, ErrorCode, ErrorKind, FromStrValue, KeyValue, NonEmptyPath, Options,
+    OsStringList, Size, SizeError, Value, ValueResult,
+};
 
 #[test]
 fn string_option() {
@@ -263,6 +269,75 @@ fn color() {
     )
 }
 
+#[test]
+fn color_explicitly_empty() {
+    #[derive(Value)]
+    enum Color {
+        #[value("yes", "always")]
+        Always,
+        #[value("auto")]
+        Auto,
+        #[value("no", "never")]
+        Never,
+    }
+
+    #[derive(Arguments)]
+    enum Arg {
+        #[arg("--color[=WHEN]")]
+        Color(Option<Color>),
+    }
+
+    #[derive(Default)]
+    struct Settings {
+        color: Option<Color>,
+    }
+
+    impl Options<Arg> for Settings {
+        fn apply(&mut self, Arg::Color(c): Arg) {
+            self.color = c;
+        }
+    }
+
+    // An explicit empty value is passed to `Value`, which rejects it because
+    // it is a prefix of every color.
+    let Err(err) = Settings::default().parse(["test", "--color="]) else {
+        panic!("an empty color should be rejected");
+    };
+    assert!(matches!(err.kind, ErrorKind::ParsingFailed { .. }));
+}
+
+#[test]
+fn optional_value_explicitly_empty() {
+    #[derive(Arguments)]
+    enum Arg {
+        #[arg("--prefix[=PREFIX]", value = String::from("> "))]
+        Prefix(String),
+    }
+
+    #[derive(Default)]
+    struct Settings {
+        prefix: Option<String>,
+    }
+
+    impl Options<Arg> for Settings {
+        fn apply(&mut self, Arg::Prefix(p): Arg) {
+            self.prefix = Some(p);
+        }
+    }
+
+    let parse = |args: &[&str]| Settings::default().parse(args).unwrap().0.prefix;
+
+    assert_eq!(parse(&["test"]), None);
+    assert_eq!(parse(&["test", "--prefix"]), Some("> ".into()));
+    assert_eq!(parse(&["test", "--prefix="]), Some("".into()));
+    assert_eq!(parse(&["test", "--prefix=#"]), Some("#".into()));
+    // An abbreviated option keeps the distinction
+    assert_eq!(parse(&["test", "--pre"]), Some("> ".into()));
+    assert_eq!(parse(&["test", "--pre="]), Some("".into()));
+    // Without an equals sign, the next argument is not taken as the value
+    assert_eq!(parse(&["test", "--prefix", "#"]), Some("> ".into()));
+}
+
 #[test]
 fn actions() {
     #[derive(Arguments)]
@@ -303,6 +378,215 @@ fn actions() {
     assert!(settings.send);
 }
 
+#[test]
+fn parse_into_existing_settings() {
+    #[derive(Arguments)]
+    enum Arg {
+        #[arg("-m MESSAGE")]
+        Message(String),
+        #[arg("--send")]
+        Send,
+    }
+
+    #[derive(Default)]
+    struct Settings {
+        messages: Vec<String>,
+        send: bool,
+    }
+
+    impl Options<Arg> for Settings {
+        fn apply(&mut self, arg: Arg) {
+            match arg {
+                Arg::Message(m) => self.messages.push(m),
+                Arg::Send => self.send = true,
+            }
+        }
+    }
+
+    let mut settings = Settings::default();
+    let operands = settings.parse_into(["config", "-m=Hello"]).unwrap();
+    assert!(operands.is_empty());
+
+    let operands = settings
+        .parse_into(["test", "-m=World", "--send", "foo"])
+        .unwrap();
+    assert_eq!(operands, vec!["foo"]);
+    assert_eq!(settings.messages, vec!["Hello", "World"]);
+    assert!(settings.send);
+}
+
+#[test]
+fn parse_indexed_operands() {
+    #[derive(Arguments)]
+    enum Arg {
+        #[arg("-m MESSAGE")]
+        Message(String),
+        #[arg("-a")]
+        A,
+        #[arg("-b")]
+        B,
+    }
+
+    #[derive(Default)]
+    struct Settings {
+        messages: Vec<String>,
+    }
+
+    impl Options<Arg> for Settings {
+        fn apply(&mut self, arg: Arg) {
+            if let Arg::Message(m) = arg {
+                self.messages.push(m);
+            }
+        }
+    }
+
+    let (settings, operands) = Settings::default()
+        .parse_indexed(["test", "-m=a", "foo", "-m", "b", "-ab", "bar", "--", "-m"])
+        .unwrap();
+    assert_eq!(
+        operands,
+        vec![
+            (2, OsString::from("foo")),
+            (6, OsString::from("bar")),
+            (8, OsString::from("-m"))
+        ]
+    );
+    assert_eq!(settings.messages, vec!["a", "b"]);
+}
+
+#[test]
+fn operands_variant() {
+    #[derive(Arguments)]
+    enum Arg {
+        #[arg("-v")]
+        Verbose,
+        #[arg(operands)]
+        Numbers(Vec<u8>),
+    }
+
+    #[derive(Default)]
+    struct Settings {
+        verbose: bool,
+        numbers: Option<(usize, Vec<u8>)>,
+    }
+
+    impl Options<Arg> for Settings {
+        fn apply(&mut self, _arg: Arg) {
+            unreachable!("apply_indexed is overridden")
+        }
+
+        fn apply_indexed(&mut self, index: usize, arg: Arg) {
+            match arg {
+                Arg::Verbose => {
+                    assert!(self.numbers.is_none(), "operands are applied last");
+                    self.verbose = true;
+                }
+                Arg::Numbers(n) => self.numbers = Some((index, n)),
+            }
+        }
+    }
+
+    let (settings, operands) = Settings::default()
+        .parse(["test", "1", "-v", "2", "--", "3"])
+        .unwrap();
+    assert!(settings.verbose);
+    assert_eq!(settings.numbers, Some((1, vec![1, 2, 3])));
+    assert!(operands.is_empty());
+
+    let (settings, _) = Settings::default().parse(["test", "-v"]).unwrap();
+    assert_eq!(settings.numbers, Some((2, vec![])));
+
+    let Err(err) = Settings::default().parse(["test", "1", "x"]) else {
+        panic!("expected an error");
+    };
+    assert!(matches!(err.kind, ErrorKind::ParsingFailed { .. }));
+    assert!(Arg::check(["test", "1", "x"]).is_err());
+
+    let errors = Arg::check_all(["test", "1", "x"]);
+    assert_eq!(errors.len(), 1);
+    assert!(matches!(errors[0].kind, ErrorKind::ParsingFailed { .. }));
+    assert!(Arg::check_all(["test", "1", "-v", "2"]).is_empty());
+}
+
+#[test]
+fn parse_without_bin_name() {
+    #[derive(Arguments)]
+    enum Arg {
+        #[arg("-v")]
+        Verbose,
+    }
+
+    #[derive(Default)]
+    struct Settings {
+        verbose: bool,
+    }
+
+    impl Options<Arg> for Settings {
+        fn apply(&mut self, Arg::Verbose: Arg) {
+            self.verbose = true;
+        }
+    }
+
+    let (settings, operands) = Settings::default()
+        .parse_without_bin_name(["-v", "foo"])
+        .unwrap();
+    assert!(settings.verbose);
+    assert_eq!(operands, ["foo"]);
+
+    let (settings, operands) = Settings::default().parse_without_bin_name(["foo"]).unwrap();
+    assert!(!settings.verbose);
+    assert_eq!(operands, ["foo"]);
+
+    let mut iter = uutils_args::ArgIterator::<Arg>::from_args_without_bin_name(["-v"]);
+    assert_eq!(iter.bin_name(), None);
+    assert!(matches!(
+        iter.next_arg(),
+        Ok(Some(uutils_args::Argument::Custom(Arg::Verbose)))
+    ));
+}
+
+#[test]
+fn apply_indexed() {
+    #[derive(Arguments)]
+    enum Arg {
+        #[arg("-m MESSAGE", "--message=MESSAGE")]
+        Message(String),
+        #[arg("-a")]
+        A,
+        #[arg("-b")]
+        B,
+    }
+
+    #[derive(Default)]
+    struct Settings {
+        indices: Vec<(usize, String)>,
+    }
+
+    impl Options<Arg> for Settings {
+        fn apply(&mut self, _arg: Arg) {
+            unreachable!("apply_indexed is overridden")
+        }
+
+        fn apply_indexed(&mut self, index: usize, arg: Arg) {
+            let name = match arg {
+                Arg::Message(m) => m,
+                Arg::A => "a".into(),
+                Arg::B => "b".into(),
+            };
+            self.indices.push((index, name));
+        }
+    }
+
+    let (settings, _) = Settings::default()
+        .parse(["test", "-m", "x", "foo", "-ab", "--message=y", "-b"])
+        .unwrap();
+    let expected = [(1, "x"), (4, "a"), (4, "b"), (5, "y"), (6, "b")];
+    assert_eq!(
+        settings.indices,
+        expected.map(|(i, name)| (i, name.to_string()))
+    );
+}
+
 #[test]
 fn width() {
     #[derive(Arguments)]
@@ -335,6 +619,79 @@ fn width() {
     );
 }
 
+#[test]
+fn size() {
+    for (input, expected) in [
+        ("1024", 1024),
+        ("1K", 1024),
+        ("1KiB", 1024),
+        ("1KB", 1000),
+        ("10k", 10 * 1024),
+        ("1kB", 1000),
+        ("512b", 512 * 512),
+        ("K", 1024),
+        ("3M", 3 * 1024 * 1024),
+        ("2GB", 2_000_000_000),
+        ("1E", 1 << 60),
+    ] {
+        assert_eq!(
+            Size::from_value(OsStr::new(input)).unwrap(),
+            Size(expected),
+            "{input}"
+        );
+    }
+
+    for input in ["", "1X", "1m", "1kiB", "-1", "1 K"] {
+        assert_eq!(
+            Size::parse(input),
+            Err(SizeError::Invalid(input.into())),
+            "{input}"
+        );
+    }
+
+    for input in ["16E", "1Z", "1YB", "99999999999999999999"] {
+        assert_eq!(
+            Size::parse(input),
+            Err(SizeError::TooLarge(input.into())),
+            "{input}"
+        );
+    }
+}
+
+#[test]
+fn non_zero_width() {
+    use std::num::NonZeroU64;
+
+    #[derive(Arguments)]
+    enum Arg {
+        #[arg("-w WIDTH")]
+        Width(NonZeroU64),
+    }
+
+    #[derive(Default, Debug)]
+    struct Settings {
+        width: Option<NonZeroU64>,
+    }
+
+    impl Options<Arg> for Settings {
+        fn apply(&mut self, Arg::Width(w): Arg) {
+            self.width = Some(w);
+        }
+    }
+
+    assert_eq!(
+        Settings::default().parse(["test", "-w=1"]).unwrap().0.width,
+        NonZeroU64::new(1)
+    );
+
+    let err = Settings::default().parse(["test", "-w=0"]).unwrap_err();
+    assert!(matches!(err.kind, ErrorKind::ParsingFailed { .. }));
+    assert!(err.to_string().contains("value must not be zero"));
+
+    let err = Settings::default().parse(["test", "-w=-1"]).unwrap_err();
+    assert!(matches!(err.kind, ErrorKind::ParsingFailed { .. }));
+}
+
 #[test]
 fn integers() {
     #[derive(Arguments)]
@@ -363,67 +720,856 @@ fn integers() {
 
     #[derive(Default)]
     struct Settings {
-        n: i128,
+        n: i128,
+    }
+
+    impl Options<Arg> for Settings {
+        fn apply(&mut self, arg: Arg) {
+            self.n = match arg {
+                Arg::U8(x) => x as i128,
+                Arg::U16(x) => x as i128,
+                Arg::U32(x) => x as i128,
+                Arg::U64(x) => x as i128,
+                Arg::U128(x) => x as i128,
+                Arg::I8(x) => x as i128,
+                Arg::I16(x) => x as i128,
+                Arg::I32(x) => x as i128,
+                Arg::I64(x) => x as i128,
+                Arg::I128(x) => x,
+            }
+        }
+    }
+
+    assert_eq!(
+        Settings::default().parse(["test", "--u8=5"]).unwrap().0.n,
+        5
+    );
+    assert_eq!(
+        Settings::default().parse(["test", "--u16=5"]).unwrap().0.n,
+        5
+    );
+    assert_eq!(
+        Settings::default().parse(["test", "--u32=5"]).unwrap().0.n,
+        5
+    );
+    assert_eq!(
+        Settings::default().parse(["test", "--u64=5"]).unwrap().0.n,
+        5
+    );
+    assert_eq!(
+        Settings::default().parse(["test", "--u128=5"]).unwrap().0.n,
+        5
+    );
+
+    assert_eq!(
+        Settings::default().parse(["test", "--i8=5"]).unwrap().0.n,
+        5
+    );
+    assert_eq!(
+        Settings::default().parse(["test", "--i16=5"]).unwrap().0.n,
+        5
+    );
+    assert_eq!(
+        Settings::default().parse(["test", "--i32=5"]).unwrap().0.n,
+        5
+    );
+    assert_eq!(
+        Settings::default().parse(["test", "--i64=5"]).unwrap().0.n,
+        5
+    );
+    assert_eq!(
+        Settings::default().parse(["test", "--i128=5"]).unwrap().0.n,
+        5
+    );
+}
+
+#[test]
+fn double_dash_ends_options() {
+    #[derive(Arguments)]
+    enum Arg {
+        #[arg("-a")]
+        A,
+        #[arg("-b")]
+        B,
+    }
+
+    #[derive(Default)]
+    struct Settings {
+        a: bool,
+        b: bool,
+    }
+
+    impl Options<Arg> for Settings {
+        fn apply(&mut self, arg: Arg) {
+            match arg {
+                Arg::A => self.a = true,
+                Arg::B => self.b = true,
+            }
+        }
+    }
+
+    let (settings, operands) = Settings::default()
+        .parse(["test", "-a", "--", "-b"])
+        .unwrap();
+    assert!(settings.a);
+    assert!(!settings.b);
+    assert_eq!(operands, ["-b"]);
+
+    let (settings, operands) = Settings::default()
+        .parse(["test", "--", "--", "-a", "foo"])
+        .unwrap();
+    assert!(!settings.a);
+    assert_eq!(operands, ["--", "-a", "foo"]);
+
+    let (settings, operands) = Settings::default().parse(["test", "-b", "--"]).unwrap();
+    assert!(settings.b);
+    assert!(operands.is_empty());
+}
+
+#[test]
+fn check_all_errors() {
+    #[allow(dead_code)]
+    #[derive(Arguments)]
+    enum Arg {
+        #[arg("-x")]
+        X,
+        #[arg("-n N", "--num=N")]
+        Num(u8),
+        #[arg("count=N")]
+        Count(u8),
+    }
+
+    let errors = Arg::check_all([
+        "test",
+        "--foo",
+        "-n",
+        "abc",
+        "-x",
+        "count=abc",
+        "-n5",
+        "-xyx",
+        "bar",
+    ]);
+    let kinds: Vec<_> = errors.iter().map(|e| &e.kind).collect();
+    assert!(matches!(
+        &kinds[..],
+        [
+            ErrorKind::UnexpectedOption(..),
+            ErrorKind::ParsingFailed { .. },
+            ErrorKind::ParsingFailed { .. },
+            ErrorKind::UnexpectedOption(..),
+        ]
+    ));
+
+    // Parsing stops at the first fatal error
+    let errors = Arg::check_all(["test", "--foo", "--num"]);
+    let kinds: Vec<_> = errors.iter().map(|e| &e.kind).collect();
+    assert!(matches!(
+        &kinds[..],
+        [
+            ErrorKind::UnexpectedOption(..),
+            ErrorKind::MissingValue { .. },
+        ]
+    ));
+
+    assert!(Arg::check_all(["test", "-x", "-n1", "count=2"]).is_empty());
+}
+
+#[test]
+fn error_codes() {
+    #[allow(dead_code)]
+    #[derive(Arguments)]
+    enum Arg {
+        #[arg("-n N", "--num=N")]
+        Num(u8),
+        #[arg("--verbose")]
+        Verbose,
+        #[arg("--version-sort")]
+        VersionSort,
+    }
+
+    let code = |args: &[&str]| Arg::check(args).unwrap_err().kind.code();
+    assert_eq!(code(&["test", "--foo"]), ErrorCode::UnexpectedOption);
+    assert_eq!(code(&["test", "-n", "abc"]), ErrorCode::ParsingFailed);
+    assert_eq!(code(&["test", "--num"]), ErrorCode::MissingValue);
+    assert_eq!(code(&["test", "--ver"]), ErrorCode::AmbiguousOption);
+    assert_eq!(code(&["test", "--verbose=1"]), ErrorCode::UnexpectedValue);
+}
+
+#[test]
+fn from_str_value() {
+    use std::str::FromStr;
+
+    #[derive(Debug, PartialEq, Eq)]
+    struct Point(i32, i32);
+
+    impl FromStr for Point {
+        type Err = String;
+
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            let (x, y) = s.split_once(',').ok_or("missing ','")?;
+            let coordinate = |c: &str| c.parse::<i32>().map_err(|e| e.to_string());
+            Ok(Point(coordinate(x)?, coordinate(y)?))
+        }
+    }
+
+    #[derive(Arguments)]
+    enum Arg {
+        #[arg("--point=POINT")]
+        Point(FromStrValue<Point>),
+    }
+
+    #[derive(Default, Debug)]
+    struct Settings {
+        point: Option<Point>,
+    }
+
+    impl Options<Arg> for Settings {
+        fn apply(&mut self, Arg::Point(FromStrValue(p)): Arg) {
+            self.point = Some(p);
+        }
+    }
+
+    let (settings, _) = Settings::default().parse(["test", "--point=1,-2"]).unwrap();
+    assert_eq!(settings.point, Some(Point(1, -2)));
+
+    let err = Settings::default()
+        .parse(["test", "--point=1"])
+        .unwrap_err();
+    assert!(err.to_string().contains("missing ','"));
+}
+
+#[test]
+fn net_values() {
+    use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+
+    let ip = |s: &str| IpAddr::from_value(OsStr::new(s));
+    assert_eq!(ip("127.0.0.1").unwrap(), Ipv4Addr::LOCALHOST);
+    assert_eq!(ip("::1").unwrap(), Ipv6Addr::LOCALHOST);
+    assert!(ip("localhost").is_err());
+
+    let v4 = Ipv4Addr::from_value(OsStr::new("127.0.0.1")).unwrap();
+    assert_eq!(v4, Ipv4Addr::LOCALHOST);
+    assert!(Ipv4Addr::from_value(OsStr::new("::1")).is_err());
+
+    let v6 = Ipv6Addr::from_value(OsStr::new("::1")).unwrap();
+    assert_eq!(v6, Ipv6Addr::LOCALHOST);
+    assert!(Ipv6Addr::from_value(OsStr::new("127.0.0.1")).is_err());
+
+    let addr = SocketAddr::from_value(OsStr::new("127.0.0.1:8080")).unwrap();
+    assert_eq!(addr, SocketAddr::from((Ipv4Addr::LOCALHOST, 8080)));
+    assert!(SocketAddr::from_value(OsStr::new("127.0.0.1")).is_err());
+}
+
+#[test]
+fn space_separated_long_value() {
+    #[derive(Arguments)]
+    enum Arg {
+        #[arg("--date=FORMAT")]
+        Date(String),
+        #[arg("--color[=WHEN]")]
+        Color(Option<String>),
+    }
+
+    #[derive(Default)]
+    struct Settings {
+        date: Option<String>,
+        color: Option<Option<String>>,
+    }
+
+    impl Options<Arg> for Settings {
+        fn apply(&mut self, arg: Arg) {
+            match arg {
+                Arg::Date(d) => self.date = Some(d),
+                Arg::Color(c) => self.color = Some(c),
+            }
+        }
+    }
+
+    // A required value can be given in the next argument
+    let (settings, operands) = Settings::default()
+        .parse(["test", "--date", "rfc-3339", "foo"])
+        .unwrap();
+    assert_eq!(settings.date.as_deref(), Some("rfc-3339"));
+    assert_eq!(operands, ["foo"]);
+
+    // Even if it looks like an option
+    let (settings, _) = Settings::default()
+        .parse(["test", "--date", "--color"])
+        .unwrap();
+    assert_eq!(settings.date.as_deref(), Some("--color"));
+    assert_eq!(settings.color, None);
+
+    // An optional value must be attached with `=`, like in GNU
+    let (settings, operands) = Settings::default()
+        .parse(["test", "--color", "always"])
+        .unwrap();
+    assert_eq!(settings.color, Some(None));
+    assert_eq!(operands, ["always"]);
+
+    let (settings, operands) = Settings::default()
+        .parse(["test", "--color=always"])
+        .unwrap();
+    assert_eq!(settings.color, Some(Some("always".into())));
+    assert!(operands.is_empty());
+
+    let Err(err) = Settings::default().parse(["test", "--date"]) else {
+        panic!("expected an error");
+    };
+    assert!(matches!(err.kind, ErrorKind::MissingValue { .. }));
+}
+
+#[test]
+fn value_in_short_cluster() {
+    #[derive(Arguments, Debug, PartialEq, Eq)]
+    enum Arg {
+        #[arg("-a")]
+        All,
+        #[arg("-b")]
+        Bytes,
+        #[arg("-I[FMT]", value = String::from("date"))]
+        Iso(String),
+        #[arg("-n N")]
+        Number(String),
+    }
+
+    #[derive(Default)]
+    struct Settings {
+        args: Vec<Arg>,
+    }
+
+    impl Options<Arg> for Settings {
+        fn apply(&mut self, arg: Arg) {
+            self.args.push(arg);
+        }
+    }
+
+    let parse = |args: &[&str]| Settings::default().parse(args).unwrap();
+
+    let (s, _) = parse(&["test", "-aIminutes"]);
+    assert_eq!(s.args, [Arg::All, Arg::Iso("minutes".into())]);
+
+    // The rest of the cluster is the value, even if it looks like flags
+    let (s, _) = parse(&["test", "-bIab"]);
+    assert_eq!(s.args, [Arg::Bytes, Arg::Iso("ab".into())]);
+
+    // An optional value at the end of a cluster does not take the next
+    // argument
+    let (s, operands) = parse(&["test", "-abI", "seconds"]);
+    assert_eq!(s.args, [Arg::All, Arg::Bytes, Arg::Iso("date".into())]);
+    assert_eq!(operands, ["seconds"]);
+
+    let (s, _) = parse(&["test", "-anb"]);
+    assert_eq!(s.args, [Arg::All, Arg::Number("b".into())]);
+
+    // A required value at the end of a cluster takes the next argument
+    let (s, operands) = parse(&["test", "-ban", "-a", "x"]);
+    assert_eq!(s.args, [Arg::Bytes, Arg::All, Arg::Number("-a".into())]);
+    assert_eq!(operands, ["x"]);
+}
+
+#[test]
+fn no_dash_value() {
+    #[derive(Arguments)]
+    enum Arg {
+        #[arg("-t DIR", "--target=DIR", no_dash_value)]
+        Target(String),
+        #[arg("-r")]
+        Recursive,
+    }
+
+    #[derive(Default)]
+    struct Settings {
+        target: String,
+        recursive: bool,
+    }
+
+    impl Options<Arg> for Settings {
+        fn apply(&mut self, arg: Arg) {
+            match arg {
+                Arg::Target(t) => self.target = t,
+                Arg::Recursive => self.recursive = true,
+            }
+        }
+    }
+
+    let parse = |args: &[&str]| Settings::default().parse(args).map(|(s, _)| s);
+
+    assert_eq!(parse(&["test", "--target", "dir"]).unwrap().target, "dir");
+    assert_eq!(parse(&["test", "-t", "dir"]).unwrap().target, "dir");
+    assert_eq!(parse(&["test", "--target", "-"]).unwrap().target, "-");
+
+    // Explicit values may start with a hyphen
+    assert_eq!(parse(&["test", "--target=-r"]).unwrap().target, "-r");
+    assert_eq!(parse(&["test", "-t-r"]).unwrap().target, "-r");
+
+    for args in [&["test", "--target", "-r"][..], &["test", "-t", "-r"]] {
+        let Err(err) = parse(args) else {
+            panic!("{args:?} should not take -r as the value");
+        };
+        let ErrorKind::MissingValue { option } = err.kind else {
+            panic!("expected a missing value, got {err}");
+        };
+        assert_eq!(option.as_deref(), Some(args[1]));
+    }
+}
+
+#[test]
+fn bytes() {
+    #[derive(Arguments)]
+    enum Arg {
+        #[arg("-d SET")]
+        Delete(Bytes),
+    }
+
+    #[derive(Default)]
+    struct Settings {
+        delete: Vec<u8>,
+    }
+
+    impl Options<Arg> for Settings {
+        fn apply(&mut self, Arg::Delete(Bytes(set)): Arg) {
+            self.delete = set;
+        }
+    }
+
+    let (s, _) = Settings::default().parse(["tr", "-d", "a\\n"]).unwrap();
+    assert_eq!(s.delete, b"a\\n");
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::ffi::OsStrExt;
+
+        let invalid = OsStr::from_bytes(&[b'a', 0xff, b'b']);
+        let (s, _) = Settings::default()
+            .parse([OsStr::new("tr"), OsStr::new("-d"), invalid])
+            .unwrap();
+        assert_eq!(s.delete, [b'a', 0xff, b'b']);
+    }
+}
+
+#[test]
+fn key_value() {
+    #[derive(Arguments)]
+    enum Arg {
+        #[arg("-D DEF", "--define=DEF")]
+        Define(KeyValue),
+    }
+
+    #[derive(Default)]
+    struct Settings {
+        defines: Vec<(OsString, OsString)>,
+    }
+
+    impl Options<Arg> for Settings {
+        fn apply(&mut self, Arg::Define(KeyValue { key, value }): Arg) {
+            self.defines.push((key, value));
+        }
+    }
+
+    let (s, _) = Settings::default()
+        .parse(["test", "-D", "a=b", "--define=a=b=c", "-Dx="])
+        .unwrap();
+    assert_eq!(
+        s.defines,
+        [
+            ("a".into(), "b".into()),
+            ("a".into(), "b=c".into()),
+            ("x".into(), "".into()),
+        ]
+    );
+
+    let Err(err) = Settings::default().parse(["test", "-D", "abc"]) else {
+        panic!("a value without '=' should be rejected");
+    };
+    assert!(matches!(err.kind, ErrorKind::ParsingFailed { .. }));
+    assert!(err.to_string().contains("KEY=VALUE"));
+}
+
+#[test]
+fn non_empty_path() {
+    #[derive(Arguments)]
+    enum Arg {
+        #[arg("--out=FILE")]
+        Out(NonEmptyPath),
+    }
+
+    #[derive(Default)]
+    struct Settings {
+        out: PathBuf,
+    }
+
+    impl Options<Arg> for Settings {
+        fn apply(&mut self, Arg::Out(NonEmptyPath(out)): Arg) {
+            self.out = out;
+        }
+    }
+
+    let (s, _) = Settings::default().parse(["test", "--out=foo"]).unwrap();
+    assert_eq!(s.out, PathBuf::from("foo"));
+
+    let Err(err) = Settings::default().parse(["test", "--out="]) else {
+        panic!("an empty path should be rejected");
+    };
+    assert!(matches!(err.kind, ErrorKind::ParsingFailed { .. }));
+    assert!(err.to_string().contains("must not be empty"));
+}
+
+#[test]
+fn validate() {
+    use uutils_args::Error;
+
+    #[derive(Arguments)]
+    enum Arg {
+        #[arg("-l")]
+        Long,
+        #[arg("-w N")]
+        Width(u16),
+    }
+
+    #[derive(Default, Debug)]
+    struct Settings {
+        long: bool,
+        width: Option<u16>,
+    }
+
+    impl Options<Arg> for Settings {
+        fn apply(&mut self, arg: Arg) {
+            match arg {
+                Arg::Long => self.long = true,
+                Arg::Width(w) => self.width = Some(w),
+            }
+        }
+
+        fn validate(&self) -> Result<(), Error> {
+            if self.long && self.width == Some(0) {
+                return Err(Error {
+                    exit_code: 2,
+                    kind: ErrorKind::Validation("-w 0 cannot be used with -l".into()),
+                });
+            }
+            Ok(())
+        }
+    }
+
+    // The order of the options does not matter, because validation happens
+    // after all of them have been applied.
+    for args in [["ls", "-l", "-w", "0"], ["ls", "-w", "0", "-l"]] {
+        let err = Settings::default().parse(args).unwrap_err();
+        assert_eq!(err.exit_code, 2);
+        assert_eq!(err.kind.code(), ErrorCode::Validation);
+        assert!(err.kind.is_usage_error());
+        assert_eq!(err.to_string(), "error: -w 0 cannot be used with -l");
+    }
+
+    assert!(Settings::default().parse(["ls", "-l", "-w", "1"]).is_ok());
+    assert!(Settings::default().parse(["ls", "-w", "0"]).is_ok());
+}
+
+#[test]
+fn value_range() {
+    #[derive(Arguments)]
+    enum Arg {
+        #[arg("-c N", "--count=N", range = 1..=100)]
+        Count(u8),
+        #[arg("--min=N", range = 10..)]
+        Min(i32),
+    }
+
+    #[derive(Default)]
+    struct Settings {
+        count: u8,
+        min: i32,
+    }
+
+    impl Options<Arg> for Settings {
+        fn apply(&mut self, arg: Arg) {
+            match arg {
+                Arg::Count(c) => self.count = c,
+                Arg::Min(m) => self.min = m,
+            }
+        }
+    }
+
+    let parse = |args: &[&str]| Settings::default().parse(args).map(|(s, _)| s);
+
+    assert_eq!(parse(&["test", "-c", "1"]).unwrap().count, 1);
+    assert_eq!(parse(&["test", "--count=100"]).unwrap().count, 100);
+    assert_eq!(parse(&["test", "--min=10"]).unwrap().min, 10);
+
+    for args in [
+        &["test", "-c", "0"][..],
+        &["test", "--count=101"],
+        &["test", "--min=9"],
+    ] {
+        let Err(err) = parse(args) else {
+            panic!("expected an error for {args:?}");
+        };
+        assert!(matches!(err.kind, ErrorKind::ParsingFailed { .. }));
+    }
+
+    let Err(err) = parse(&["test", "--count=101"]) else {
+        panic!("expected an error");
+    };
+    assert!(err.to_string().contains("value 101 out of range 1..=100"));
+}
+
+#[test]
+fn custom_parser() {
+    // Tab sizes can be given as a number or as "tab", which means 8. The
+    // type is a plain u8, so Value cannot be implemented for it here.
+    fn parse_tab_size(value: &OsStr) -> ValueResult<u8> {
+        match value.to_str() {
+            Some("tab") => Ok(8),
+            Some(s) => Ok(s.parse()?),
+            None => Err("invalid unicode".into()),
+        }
+    }
+
+    #[derive(Arguments)]
+    enum Arg {
+        #[arg("-t N", "--tabsize=N", parser = parse_tab_size)]
+        TabSize(u8),
+        #[arg("--indent=N", range = 1..=4, parser = parse_tab_size)]
+        Indent(u8),
+    }
+
+    #[derive(Default)]
+    struct Settings {
+        tab_size: u8,
+        indent: u8,
+    }
+
+    impl Options<Arg> for Settings {
+        fn apply(&mut self, arg: Arg) {
+            match arg {
+                Arg::TabSize(n) => self.tab_size = n,
+                Arg::Indent(n) => self.indent = n,
+            }
+        }
+    }
+
+    let parse = |args: &[&str]| Settings::default().parse(args).map(|(s, _)| s);
+
+    assert_eq!(parse(&["test", "-t", "4"]).unwrap().tab_size, 4);
+    assert_eq!(parse(&["test", "--tabsize=tab"]).unwrap().tab_size, 8);
+    assert_eq!(parse(&["test", "--indent=2"]).unwrap().indent, 2);
+
+    let Err(err) = parse(&["test", "--tabsize=x"]) else {
+        panic!("expected an error");
+    };
+    assert!(matches!(
+        err.kind,
+        ErrorKind::ParsingFailed { ref option, ref value, .. } if option == "--tabsize" && value == "x"
+    ));
+
+    // The range is checked after the custom parser
+    let Err(err) = parse(&["test", "--indent=tab"]) else {
+        panic!("expected an error");
+    };
+    assert!(err.to_string().contains("value tab out of range 1..=4"));
+}
+
+#[test]
+fn greedy() {
+    #[derive(Arguments)]
+    enum Arg {
+        #[arg("-v", "--verbose")]
+        Verbose,
+        #[arg("-e CMD", "--exec=CMD", greedy)]
+        Exec(Vec<String>),
+    }
+
+    #[derive(Default, Debug)]
+    struct Settings {
+        verbose: bool,
+        exec: Vec<String>,
+    }
+
+    impl Options<Arg> for Settings {
+        fn apply(&mut self, arg: Arg) {
+            match arg {
+                Arg::Verbose => self.verbose = true,
+                Arg::Exec(cmd) => self.exec = cmd,
+            }
+        }
+    }
+
+    let (s, operands) = Settings::default()
+        .parse(["test", "-v", "--exec", "ls", "-l", "--verbose"])
+        .unwrap();
+    assert!(s.verbose);
+    assert_eq!(s.exec, ["ls", "-l", "--verbose"]);
+    assert!(operands.is_empty());
+
+    let (s, _) = Settings::default()
+        .parse(["test", "--exec=ls", "-a"])
+        .unwrap();
+    assert_eq!(s.exec, ["ls", "-a"]);
+
+    let (s, _) = Settings::default().parse(["test", "-els", "-a"]).unwrap();
+    assert_eq!(s.exec, ["ls", "-a"]);
+
+    // The values end at "--", after which the operands start
+    let (s, operands) = Settings::default()
+        .parse(["test", "-e", "ls", "-a", "--", "-v", "file"])
+        .unwrap();
+    assert!(!s.verbose);
+    assert_eq!(s.exec, ["ls", "-a"]);
+    assert_eq!(operands, ["-v", "file"]);
+
+    for args in [&["test", "--exec"][..], &["test", "-e", "--", "ls"]] {
+        let err = Settings::default().parse(args).unwrap_err();
+        assert!(matches!(err.kind, ErrorKind::MissingValue { .. }), "{err}");
+    }
+}
+
+#[test]
+fn terminator() {
+    #[derive(Arguments)]
+    enum Arg {
+        #[arg("-v", "--verbose")]
+        Verbose,
+        #[arg("-e", "--end", terminator)]
+        End,
+    }
+
+    #[derive(Default, Debug)]
+    struct Settings {
+        verbose: usize,
+        end: bool,
+    }
+
+    impl Options<Arg> for Settings {
+        fn apply(&mut self, arg: Arg) {
+            match arg {
+                Arg::Verbose => self.verbose += 1,
+                Arg::End => self.end = true,
+            }
+        }
+    }
+
+    let (s, operands) = Settings::default()
+        .parse_indexed(["test", "-v", "a", "--end", "-v", "--", "b"])
+        .unwrap();
+    assert_eq!(s.verbose, 1);
+    assert!(s.end);
+    assert_eq!(
+        operands,
+        [
+            (2, "a".into()),
+            (4, "-v".into()),
+            (5, "--".into()),
+            (6, "b".into())
+        ]
+    );
+
+    // The rest of a cluster is an operand as well
+    let (s, operands) = Settings::default().parse(["test", "-vevv", "-v"]).unwrap();
+    assert_eq!(s.verbose, 1);
+    assert_eq!(operands, ["-vv", "-v"]);
+
+    let (s, operands) = Settings::default().parse(["test", "-v"]).unwrap();
+    assert!(!s.end);
+    assert!(operands.is_empty());
+
+    let err = Settings::default().parse(["test", "--end=x"]).unwrap_err();
+    assert!(
+        matches!(err.kind, ErrorKind::UnexpectedValue { .. }),
+        "{err}"
+    );
+}
+
+#[test]
+fn multiple_values() {
+    #[derive(Arguments)]
+    enum Arg {
+        #[arg("-s W H", "--size W H", nargs = 2)]
+        Size([u32; 2]),
+        #[arg("--label NAME X Y", nargs = 3)]
+        Label((String, i32, i32)),
+    }
+
+    #[derive(Default, Debug)]
+    struct Settings {
+        size: [u32; 2],
+        labels: Vec<(String, i32, i32)>,
+    }
+
+    impl Options<Arg> for Settings {
+        fn apply(&mut self, arg: Arg) {
+            match arg {
+                Arg::Size(size) => self.size = size,
+                Arg::Label(label) => self.labels.push(label),
+            }
+        }
+    }
+
+    let (settings, operands) = Settings::default()
+        .parse(["test", "--size", "3", "4", "foo"])
+        .unwrap();
+    assert_eq!(settings.size, [3, 4]);
+    assert_eq!(operands, ["foo"]);
+
+    let (settings, _) = Settings::default()
+        .parse([
+            "test", "-s5", "6", "--label", "a", "1", "-2", "--size=7", "8",
+        ])
+        .unwrap();
+    assert_eq!(settings.size, [7, 8]);
+    assert_eq!(settings.labels, [("a".to_string(), 1, -2)]);
+
+    let err = Settings::default()
+        .parse(["test", "--size", "3"])
+        .unwrap_err();
+    assert!(matches!(err.kind, ErrorKind::MissingValue { .. }));
+
+    let help = Arg::help("test");
+    assert!(help.contains("-s W H, --size W H"));
+}
+
+#[test]
+fn os_string_list() {
+    #[derive(Arguments)]
+    enum Arg {
+        #[arg("--path=PATH")]
+        Path(OsStringList),
+        #[arg("--list=LIST")]
+        List(OsStringList<','>),
+    }
+
+    #[derive(Default)]
+    struct Settings {
+        path: Vec<OsString>,
+        list: Vec<OsString>,
     }
 
     impl Options<Arg> for Settings {
         fn apply(&mut self, arg: Arg) {
-            self.n = match arg {
-                Arg::U8(x) => x as i128,
-                Arg::U16(x) => x as i128,
-                Arg::U32(x) => x as i128,
-                Arg::U64(x) => x as i128,
-                Arg::U128(x) => x as i128,
-                Arg::I8(x) => x as i128,
-                Arg::I16(x) => x as i128,
-                Arg::I32(x) => x as i128,
-                Arg::I64(x) => x as i128,
-                Arg::I128(x) => x,
+            match arg {
+                Arg::Path(OsStringList(p)) => self.path = p,
+                Arg::List(OsStringList(l)) => self.list = l,
             }
         }
     }
 
-    assert_eq!(
-        Settings::default().parse(["test", "--u8=5"]).unwrap().0.n,
-        5
-    );
-    assert_eq!(
-        Settings::default().parse(["test", "--u16=5"]).unwrap().0.n,
-        5
-    );
-    assert_eq!(
-        Settings::default().parse(["test", "--u32=5"]).unwrap().0.n,
-        5
-    );
-    assert_eq!(
-        Settings::default().parse(["test", "--u64=5"]).unwrap().0.n,
-        5
-    );
-    assert_eq!(
-        Settings::default().parse(["test", "--u128=5"]).unwrap().0.n,
-        5
-    );
+    let (settings, _) = Settings::default()
+        .parse(["test", "--path=/a:/b::/c", "--list=x,y:z"])
+        .unwrap();
+    assert_eq!(settings.path, ["/a", "/b", "", "/c"]);
+    assert_eq!(settings.list, ["x", "y:z"]);
+}
 
-    assert_eq!(
-        Settings::default().parse(["test", "--i8=5"]).unwrap().0.n,
-        5
-    );
-    assert_eq!(
-        Settings::default().parse(["test", "--i16=5"]).unwrap().0.n,
-        5
-    );
-    assert_eq!(
-        Settings::default().parse(["test", "--i32=5"]).unwrap().0.n,
-        5
-    );
-    assert_eq!(
-        Settings::default().parse(["test", "--i64=5"]).unwrap().0.n,
-        5
-    );
-    assert_eq!(
-        Settings::default().parse(["test", "--i128=5"]).unwrap().0.n,
-        5
-    );
+#[test]
+#[cfg(unix)]
+fn os_string_list_invalid_utf8() {
+    use std::os::unix::ffi::{OsStrExt, OsStringExt};
+
+    let value = OsString::from_vec(b"/a\xff:/b".to_vec());
+    let OsStringList(list) = OsStringList::<':'>::from_value(&value).unwrap();
+    assert_eq!(list.len(), 2);
+    assert_eq!(list[0].as_bytes(), b"/a\xff");
+    assert_eq!(list[1].as_bytes(), b"/b");
 }
 
 #[test]
@@ -545,6 +1691,126 @@ fn infer_value() {
     Foo::from_value(OsStr::new("de")).unwrap_err();
 }
 
+#[test]
+fn value_ambiguity() {
+    #[derive(Value, PartialEq, Eq, Debug)]
+    enum Error {
+        #[value("deck")]
+        Deck,
+        #[value("desk")]
+        Desk,
+        #[value("default")]
+        Default,
+    }
+
+    #[derive(Value, PartialEq, Eq, Debug)]
+    #[value(ambiguity = "first")]
+    enum First {
+        #[value("deck")]
+        Deck,
+        #[value("desk")]
+        Desk,
+        #[value("default")]
+        Default,
+    }
+
+    #[derive(Value, PartialEq, Eq, Debug)]
+    #[value(ambiguity = "exact_only")]
+    enum ExactOnly {
+        #[value("deck")]
+        Deck,
+        #[value("desk")]
+        Desk,
+        #[value("default")]
+        Default,
+    }
+
+    let err = Error::from_value(OsStr::new("de")).unwrap_err();
+    assert!(
+        err.to_string().contains("- deck  - desk  - default"),
+        "{err}"
+    );
+    assert_eq!(
+        Error::from_value(OsStr::new("def")).unwrap(),
+        Error::Default
+    );
+
+    assert_eq!(First::from_value(OsStr::new("de")).unwrap(), First::Deck);
+    assert_eq!(First::from_value(OsStr::new("des")).unwrap(), First::Desk);
+    assert_eq!(
+        First::from_value(OsStr::new("default")).unwrap(),
+        First::Default
+    );
+    First::from_value(OsStr::new("x")).unwrap_err();
+
+    for prefix in ["de", "def"] {
+        let err = ExactOnly::from_value(OsStr::new(prefix)).unwrap_err();
+        assert_eq!(err.to_string(), "Invalid value");
+    }
+    assert_eq!(
+        ExactOnly::from_value(OsStr::new("desk")).unwrap(),
+        ExactOnly::Desk
+    );
+}
+
+#[test]
+#[cfg(unix)]
+fn value_invalid_utf8() {
+    use std::os::unix::ffi::OsStrExt;
+
+    #[derive(Value, PartialEq, Eq, Debug)]
+    enum Foo {
+        #[value("long")]
+        Long,
+        #[value("équipe")]
+        Team,
+    }
+
+    // Exact matches are compared by their bytes
+    assert_eq!(
+        Foo::from_value(OsStr::from_bytes("équipe".as_bytes())).unwrap(),
+        Foo::Team
+    );
+
+    // Not valid UTF-8 is a mismatch and not an error about the encoding,
+    // even if it is a prefix of a key in bytes.
+    for invalid in [&b"\xff"[..], b"lo\xff", &"é".as_bytes()[..1]] {
+        let err = Foo::from_value(OsStr::from_bytes(invalid)).unwrap_err();
+        assert_eq!(err.to_string(), "Invalid value");
+    }
+}
+
+#[test]
+fn value_other() {
+    #[derive(Value, PartialEq, Eq, Debug)]
+    enum Foo {
+        #[value("long")]
+        Long,
+        #[value("link")]
+        Link,
+        #[value(other)]
+        Other(OsString),
+    }
+
+    assert_eq!(Foo::from_value(OsStr::new("long")).unwrap(), Foo::Long);
+    assert_eq!(Foo::from_value(OsStr::new("lo")).unwrap(), Foo::Long);
+    assert_eq!(
+        Foo::from_value(OsStr::new("short")).unwrap(),
+        Foo::Other("short".into())
+    );
+    Foo::from_value(OsStr::new("l")).unwrap_err();
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::ffi::OsStrExt;
+        let invalid = OsStr::from_bytes(b"\xff");
+        assert_eq!(
+            Foo::from_value(invalid).unwrap(),
+            Foo::Other(invalid.into())
+        );
+    }
+}
+
 #[test]
 fn deprecated() {
     fn parse_minus(s: &str) -> Option<&str> {
@@ -604,13 +1870,107 @@ fn deprecated() {
     );
 }
 
+#[test]
+fn plus_prefixed() {
+    #[derive(Arguments)]
+    enum Arg {
+        #[arg("+N")]
+        Number(isize),
+        #[arg("+FORMAT")]
+        Format(String),
+        #[arg("-n")]
+        N,
+    }
+
+    #[derive(Default)]
+    struct Settings {
+        number: Option<isize>,
+        format: Option<String>,
+    }
+
+    impl Options<Arg> for Settings {
+        fn apply(&mut self, arg: Arg) {
+            match arg {
+                Arg::Number(n) => self.number = Some(n),
+                Arg::Format(f) => self.format = Some(f),
+                Arg::N => {}
+            }
+        }
+    }
+
+    let (settings, operands) = Settings::default()
+        .parse(["date", "+%s", "-n", "foo"])
+        .unwrap();
+    assert_eq!(settings.format.as_deref(), Some("%s"));
+    assert_eq!(settings.number, None);
+    assert_eq!(operands, ["foo"]);
+
+    let (settings, _) = Settings::default().parse(["date", "+5"]).unwrap();
+    assert_eq!(settings.number, Some(5));
+    assert_eq!(settings.format, None);
+
+    let (settings, _) = Settings::default().parse(["date", "+-5"]).unwrap();
+    assert_eq!(settings.number, Some(-5));
+
+    let help = Arg::help("date");
+    assert!(help.contains("+FORMAT"));
+}
+
+#[test]
+fn plus_prefixed_with_parser() {
+    // Only formats with a conversion are accepted
+    fn parse_format(value: &OsStr) -> ValueResult<String> {
+        match value.to_str() {
+            Some(s) if s.contains('%') => Ok(s.into()),
+            _ => Err("invalid format".into()),
+        }
+    }
+
+    #[derive(Arguments)]
+    enum Arg {
+        #[arg("+FORMAT", parser = parse_format)]
+        Format(String),
+        #[arg("+N", range = 1..=10)]
+        Number(u8),
+    }
+
+    #[derive(Default)]
+    struct Settings {
+        format: Option<String>,
+        number: Option<u8>,
+    }
+
+    impl Options<Arg> for Settings {
+        fn apply(&mut self, arg: Arg) {
+            match arg {
+                Arg::Format(f) => self.format = Some(f),
+                Arg::Number(n) => self.number = Some(n),
+            }
+        }
+    }
+
+    let (settings, operands) = Settings::default().parse(["date", "+%s"]).unwrap();
+    assert_eq!(settings.format.as_deref(), Some("%s"));
+    assert!(operands.is_empty());
+
+    // Values rejected by the parser or out of range are not taken
+    let (settings, operands) = Settings::default()
+        .parse(["date", "+5", "+foo", "+20"])
+        .unwrap();
+    assert_eq!(settings.format, None);
+    assert_eq!(settings.number, Some(5));
+    assert_eq!(operands, ["+foo", "+20"]);
+}
+
 #[test]
 #[allow(unreachable_code)]
 fn empty_value() {
     // We just check that this compiles
+    #[allow(dead_code)]
     #[derive(Value)]
     enum V {}
 
+    #[allow(dead_code)]
     #[derive(Arguments)]
     enum Arg {
         #[arg("--val=VAL")]
@@ -628,3 +1988,248 @@ fn empty_value() {
         }
     }
 }
+
+#[test]
+fn response_files() {
+    #[derive(Arguments)]
+    #[arguments(response_files, error_exit_code = 2)]
+    enum Arg {
+        #[arg("-v", "--verbose")]
+        Verbose,
+        #[arg("-n NAME", "--name=NAME")]
+        Name(String),
+    }
+
+    #[derive(Default, Debug)]
+    struct Settings {
+        verbose: bool,
+        names: Vec<String>,
+    }
+
+    impl Options<Arg> for Settings {
+        fn apply(&mut self, arg: Arg) {
+            match arg {
+                Arg::Verbose => self.verbose = true,
+                Arg::Name(name) => self.names.push(name),
+            }
+        }
+    }
+
+    let dir = std::env::temp_dir().join(format!("uutils-args-response-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let file = |name: &str, contents: &str| {
+        let path = dir.join(name);
+        std::fs::write(&path, contents).unwrap();
+        format!("@{}", path.display())
+    };
+
+    let inner = file("inner", "--name=inner\n");
+    let outer = file("outer", &format!("-v\n--name\nwith space\n{inner}\nfile\n"));
+    let (s, operands) = Settings::default()
+        .parse(["test", &outer, "last", "--", &inner])
+        .unwrap();
+    assert!(s.verbose);
+    assert_eq!(s.names, ["with space", "inner"]);
+    assert_eq!(operands, ["file", "last", inner.as_str()]);
+
+    // Values of options are expanded too, until a `--`
+    let value = file("value", "from file\n");
+    let (s, operands) = Settings::default()
+        .parse(["test", "--name", &value, "-n", "--", &value])
+        .unwrap();
+    assert_eq!(s.names, ["from file", "--"]);
+    assert_eq!(operands, [value.as_str()]);
+
+    // Only Windows line endings are stripped where they are common
+    let crlf = file("crlf", "--name=crlf\r\n");
+    let (s, _) = Settings::default().parse(["test", &crlf]).unwrap();
+    let expected = if cfg!(unix) { "crlf\r" } else { "crlf" };
+    assert_eq!(s.names, [expected]);
+
+    // A lone @ is an operand
+    let (_, operands) = Settings::default().parse(["test", "@"]).unwrap();
+    assert_eq!(operands, ["@"]);
+
+    let missing = format!("@{}", dir.join("missing").display());
+    let err = Settings::default().parse(["test", &missing]).unwrap_err();
+    assert_eq!(err.exit_code, 2);
+    assert_eq!(err.kind.code(), ErrorCode::ResponseFile);
+    assert!(!err.kind.is_usage_error());
+
+    let recursive = dir.join("recursive");
+    std::fs::write(&recursive, format!("@{}\n", recursive.display())).unwrap();
+    let recursive = format!("@{}", recursive.display());
+    let err = Settings::default().parse(["test", &recursive]).unwrap_err();
+    assert!(
+        err.to_string().contains("too many nested response files"),
+        "{err}"
+    );
+    assert_eq!(Arg::check_all(["test", &recursive]).len(), 1);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    // Without the attribute, response files are operands
+    #[derive(Arguments)]
+    enum Plain {}
+
+    #[derive(Default)]
+    struct PlainSettings;
+
+    impl Options<Plain> for PlainSettings {
+        fn apply(&mut self, arg: Plain) {
+            match arg {}
+        }
+    }
+
+    let (_, operands) = PlainSettings.parse(["test", &inner]).unwrap();
+    assert_eq!(operands, [inner.as_str()]);
+}
+
+#[test]
+fn error_template() {
+    #[allow(dead_code)]
+    #[derive(Arguments)]
+    enum Arg {
+        #[arg("-m MODE", "--mode=MODE", error = "invalid mode: '{value}'")]
+        Mode(u32),
+        #[arg("--depth=N", range = 1..=3, error = "{option}: {error} {{N}} {{value}}")]
+        Depth(u8),
+        #[arg("--width=N")]
+        Width(u32),
+    }
+
+    struct Settings;
+
+    impl Options<Arg> for Settings {
+        fn apply(&mut self, _arg: Arg) {}
+    }
+
+    let message = |args: &[&str]| {
+        let Err(err) = Settings.parse(args) else {
+            panic!("{args:?} should fail to parse");
+        };
+        assert!(matches!(err.kind, ErrorKind::ParsingFailed { .. }));
+        let message = err.to_string();
+        message.strip_prefix("error: ").unwrap().to_string()
+    };
+
+    assert_eq!(message(&["test", "--mode=x"]), "invalid mode: 'x'");
+    assert_eq!(
+        message(&["test", "-m", "{option}"]),
+        "invalid mode: '{option}'"
+    );
+    assert_eq!(
+        message(&["test", "--depth=5"]),
+        "--depth: value 5 out of range 1..=3 {N} {value}"
+    );
+    assert!(message(&["test", "--width=x"]).starts_with("Invalid value 'x' for '--width'"));
+}
+
+#[test]
+fn required_group() {
+    #[allow(dead_code)]
+    #[derive(Arguments)]
+    #[arguments(required_group = ["Bytes", "Characters", "Fields"])]
+    enum Arg {
+        #[arg("-b LIST", "--bytes=LIST")]
+        Bytes(String),
+        #[arg("-c LIST", "--characters=LIST")]
+        Characters(String),
+        #[arg("-f LIST")]
+        Fields(String),
+        #[arg("-s", "--only-delimited")]
+        OnlyDelimited,
+    }
+
+    #[derive(Default, Debug)]
+    struct Settings {
+        lists: Vec<String>,
+    }
+
+    impl Options<Arg> for Settings {
+        fn apply(&mut self, arg: Arg) {
+            if let Arg::Bytes(list) | Arg::Characters(list) | Arg::Fields(list) = arg {
+                self.lists.push(list);
+            }
+        }
+    }
+
+    let (s, _) = Settings::default().parse(["cut", "-f1", "-s"]).unwrap();
+    assert_eq!(s.lists, ["1"]);
+    let (s, _) = Settings::default().parse(["cut", "-b1", "-b2"]).unwrap();
+    assert_eq!(s.lists, ["1", "2"]);
+
+    let err = Settings::default().parse(["cut", "-s"]).unwrap_err();
+    assert!(matches!(
+        err.kind,
+        ErrorKind::MissingRequiredGroup(options) if options == ["--bytes", "--characters", "-f"]
+    ));
+
+    let err = Settings::default()
+        .parse(["cut", "-f1", "-c2", "-s"])
+        .unwrap_err();
+    assert!(matches!(
+        err.kind,
+        ErrorKind::TooManyInGroup(options) if options == ["--characters", "-f"]
+    ));
+
+    assert!(matches!(
+        Arg::check(["cut", "-s"]).unwrap_err().kind,
+        ErrorKind::MissingRequiredGroup(_)
+    ));
+    let errors = Arg::check_all(["cut", "-b1", "-f1"]);
+    assert_eq!(errors.len(), 1);
+    assert!(matches!(errors[0].kind, ErrorKind::TooManyInGroup(_)));
+    let err = Settings::default()
+        .parse_until_operand(["cut", "-s", "file"])
+        .unwrap_err();
+    assert!(matches!(err.kind, ErrorKind::MissingRequiredGroup(_)));
+    let err = Settings::default()
+        .parse_until_operand(["cut", "-b1", "-f1", "file", "-c1"])
+        .unwrap_err();
+    assert!(matches!(err.kind, ErrorKind::TooManyInGroup(_)));
+    let (s, rest) = Settings::default()
+        .parse_until_operand(["cut", "-f1", "file", "-c1"])
+        .unwrap();
+    assert_eq!(s.lists, ["1"]);
+    assert_eq!(rest.collect::<Vec<_>>(), ["file", "-c1"]);
+    // The help is not affected by the group
+    assert!(matches!(
+        Arg::check(["cut", "--help"]).unwrap_err().kind,
+        ErrorKind::HelpRequested(_)
+    ));
+}
+
+#[test]
+fn integer_out_of_range() {
+    let error = |value: &str| u16::from_value(OsStr::new(value)).unwrap_err().to_string();
+    assert_eq!(
+        error("99999"),
+        "value 99999 out of range for u16 (0..=65535)"
+    );
+    assert_eq!(error("x"), "invalid digit found in string");
+    assert_eq!(
+        i8::from_value(OsStr::new("-129")).unwrap_err().to_string(),
+        "value -129 out of range for i8 (-128..=127)"
+    );
+
+    #[allow(dead_code)]
+    #[derive(Arguments)]
+    enum Arg {
+        #[arg("--u8=N")]
+        U8(u8),
+    }
+
+    struct Settings;
+
+    impl Options<Arg> for Settings {
+        fn apply(&mut self, Arg::U8(_): Arg) {}
+    }
+
+    let Err(err) = Settings.parse(["test", "--u8=300"]) else {
+        panic!("300 should not fit in a u8");
+    };
+    assert!(err
+        .to_string()
+        .ends_with("Invalid value '300' for '--u8': value 300 out of range for u8 (0..=255)"));
+}
@@ -60,3 +60,206 @@ fn timeout_like() {
     assert!(!settings.verbose);
     assert_eq!(command, vec![OsString::from("10"), OsString::from("-v")]);
 }
+
+#[test]
+fn indexed() {
+    #[derive(Arguments)]
+    #[arguments(options_first)]
+    enum Arg {
+        #[arg("-v", "--verbose")]
+        Verbose,
+    }
+
+    struct Settings;
+
+    impl Options<Arg> for Settings {
+        fn apply(&mut self, _arg: Arg) {}
+    }
+
+    let (_, operands) = Settings
+        .parse_indexed(["timeout", "-v", "10", "foo", "-v"])
+        .unwrap();
+    assert_eq!(
+        operands,
+        vec![
+            (2, OsString::from("10")),
+            (3, OsString::from("foo")),
+            (4, OsString::from("-v"))
+        ]
+    );
+}
+
+#[test]
+fn parse_until_operand() {
+    // Like env, which passes the rest of the arguments to a command
+    #[derive(Arguments)]
+    enum Arg {
+        #[arg("-i", "--ignore-environment")]
+        IgnoreEnvironment,
+        #[arg("-u NAME", "--unset=NAME")]
+        Unset(OsString),
+    }
+
+    #[derive(Default)]
+    struct Settings {
+        ignore_environment: bool,
+        unset: Vec<OsString>,
+    }
+
+    impl Options<Arg> for Settings {
+        fn apply(&mut self, arg: Arg) {
+            match arg {
+                Arg::IgnoreEnvironment => self.ignore_environment = true,
+                Arg::Unset(name) => self.unset.push(name),
+            }
+        }
+    }
+
+    let (settings, rest) = Settings::default()
+        .parse_until_operand(["env", "-i", "-u", "FOO", "ls", "-l", "--", "-i"])
+        .unwrap();
+    assert!(settings.ignore_environment);
+    assert_eq!(settings.unset, vec![OsString::from("FOO")]);
+    assert_eq!(
+        rest.collect::<Vec<_>>(),
+        vec![
+            OsString::from("ls"),
+            OsString::from("-l"),
+            OsString::from("--"),
+            OsString::from("-i")
+        ]
+    );
+
+    let (settings, rest) = Settings::default()
+        .parse_until_operand(["env", "--", "-i"])
+        .unwrap();
+    assert!(!settings.ignore_environment);
+    assert_eq!(rest.collect::<Vec<_>>(), vec![OsString::from("-i")]);
+
+    let (settings, mut rest) = Settings::default()
+        .parse_until_operand(["env", "-i"])
+        .unwrap();
+    assert!(settings.ignore_environment);
+    assert!(rest.next().is_none());
+
+    assert!(Settings::default()
+        .parse_until_operand(["env", "-x", "ls"])
+        .is_err());
+}
+
+#[test]
+fn passthrough_unknown() {
+    #[derive(Arguments)]
+    #[arguments(passthrough_unknown)]
+    enum Arg {
+        #[arg("-v", "--verbose")]
+        Verbose,
+    }
+
+    #[derive(Default)]
+    struct Settings {
+        verbose: bool,
+    }
+
+    impl Options<Arg> for Settings {
+        fn apply(&mut self, Arg::Verbose: Arg) {
+            self.verbose = true;
+        }
+    }
+
+    let (settings, rest) = Settings::default()
+        .parse([
+            "wrap",
+            "--foo=bar",
+            "a",
+            "-vx",
+            "--ver",
+            "--baz",
+            "b",
+            "--",
+            "-y",
+        ])
+        .unwrap();
+    assert!(settings.verbose);
+    assert_eq!(
+        rest,
+        ["--foo=bar", "a", "-x", "--baz", "b", "--", "-y"].map(OsString::from)
+    );
+
+    // The `--` is only kept if it is needed to forward the arguments after it
+    let (_, rest) = Settings::default()
+        .parse(["wrap", "--foo", "--", "a", "-"])
+        .unwrap();
+    assert_eq!(rest, ["--foo", "a", "-"].map(OsString::from));
+
+    // Ambiguous options are still an error
+    #[derive(Arguments)]
+    #[arguments(passthrough_unknown)]
+    enum Ambiguous {
+        #[arg("--verbose")]
+        Verbose,
+        #[arg("--version-sort")]
+        VersionSort,
+    }
+
+    struct Nothing;
+
+    impl Options<Ambiguous> for Nothing {
+        fn apply(&mut self, _: Ambiguous) {}
+    }
+
+    assert!(Nothing.parse(["wrap", "--ver"]).is_err());
+
+    // A cluster with an unknown flag is handed off as a whole
+    let (settings, rest) = Settings::default()
+        .parse_until_operand(["wrap", "-vxz", "-v"])
+        .unwrap();
+    assert!(settings.verbose);
+    assert_eq!(rest.collect::<Vec<_>>(), ["-xz", "-v"].map(OsString::from));
+}
+
+#[test]
+fn parse_until_operand_indexed() {
+    #[derive(Arguments)]
+    enum Arg {
+        #[arg("-a")]
+        A,
+        #[arg("-b")]
+        B,
+        #[arg("-u NAME")]
+        Unset(String),
+    }
+
+    #[derive(Default)]
+    struct Settings {
+        indices: Vec<(usize, String)>,
+    }
+
+    impl Options<Arg> for Settings {
+        fn apply(&mut self, _arg: Arg) {
+            unreachable!("apply_indexed is overridden")
+        }
+
+        fn apply_indexed(&mut self, index: usize, arg: Arg) {
+            let name = match arg {
+                Arg::A => "a".into(),
+                Arg::B => "b".into(),
+                Arg::Unset(name) => name,
+            };
+            self.indices.push((index, name));
+        }
+    }
+
+    let (settings, rest) = Settings::default()
+        .parse_until_operand(["env", "-u", "FOO", "-ab", "-b", "ls", "-a"])
+        .unwrap();
+    let expected = [(1, "FOO"), (3, "a"), (3, "b"), (4, "b")];
+    assert_eq!(
+        settings.indices,
+        expected.map(|(i, name)| (i, name.to_string()))
+    );
+    assert_eq!(
+        rest.collect::<Vec<_>>(),
+        vec![OsString::from("ls"), OsString::from("-a")]
+    );
+}
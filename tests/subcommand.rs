@@ -0,0 +1,149 @@
+use std::ffi::OsString;
+
+use uutils_args::{ArgIterator, Argument, Arguments, Options};
+
+#[derive(Arguments)]
+enum AddArg {
+    #[arg("-f", "--force")]
+    Force,
+}
+
+#[derive(Arguments)]
+enum RemoveArg {
+    #[arg("-r", "--recursive")]
+    Recursive,
+}
+
+#[derive(Arguments)]
+enum Arg {
+    #[arg("-v", "--verbose")]
+    Verbose,
+
+    /// Add files
+    #[arg(subcommand)]
+    Add(AddArg),
+
+    /// Remove files
+    #[arg(subcommand = "rm")]
+    Remove(RemoveArg),
+}
+
+#[derive(Default, Debug, PartialEq, Eq)]
+struct Settings {
+    verbose: bool,
+    force: bool,
+    recursive: bool,
+}
+
+impl Options<Arg> for Settings {
+    fn apply(&mut self, arg: Arg) {
+        match arg {
+            Arg::Verbose => self.verbose = true,
+            Arg::Add(AddArg::Force) => self.force = true,
+            Arg::Remove(RemoveArg::Recursive) => self.recursive = true,
+        }
+    }
+}
+
+#[test]
+fn no_subcommand() {
+    let (settings, operands) = Settings::default().parse(["git", "-v"]).unwrap();
+    assert_eq!(
+        settings,
+        Settings {
+            verbose: true,
+            ..Settings::default()
+        }
+    );
+    assert!(operands.is_empty());
+}
+
+#[test]
+fn subcommand_from_variant_name() {
+    let (settings, operands) = Settings::default()
+        .parse(["git", "-v", "add", "--force", "file"])
+        .unwrap();
+    assert_eq!(
+        settings,
+        Settings {
+            verbose: true,
+            force: true,
+            ..Settings::default()
+        }
+    );
+    assert_eq!(
+        operands,
+        vec![OsString::from("add"), OsString::from("file")]
+    );
+}
+
+#[test]
+fn subcommand_with_custom_name() {
+    let (settings, operands) = Settings::default().parse(["git", "rm", "-r"]).unwrap();
+    assert!(settings.recursive);
+    assert_eq!(operands, vec![OsString::from("rm")]);
+
+    assert!(Settings::default().parse(["git", "remove", "-r"]).is_err());
+}
+
+#[test]
+fn options_are_scoped_to_subcommand() {
+    // Options of the parent command are not accepted after the subcommand
+    assert!(Settings::default().parse(["git", "add", "-v"]).is_err());
+
+    // Options of the subcommand are not accepted before the subcommand
+    assert!(Settings::default().parse(["git", "-f", "add"]).is_err());
+}
+
+#[test]
+fn subcommand_must_be_first_operand() {
+    let (settings, operands) = Settings::default()
+        .parse(["git", "file", "add", "-v"])
+        .unwrap();
+    assert!(settings.verbose);
+    assert_eq!(
+        operands,
+        vec![OsString::from("file"), OsString::from("add")]
+    );
+
+    let (_, operands) = Settings::default()
+        .parse(["git", "add", "rm", "-f"])
+        .unwrap();
+    assert_eq!(operands, vec![OsString::from("add"), OsString::from("rm")]);
+}
+
+#[test]
+fn arg_iterator() {
+    let mut iter = ArgIterator::<Arg>::from_args(["git", "-v", "add", "-f", "--help"]);
+    assert!(matches!(
+        iter.next_arg(),
+        Ok(Some(Argument::Custom(Arg::Verbose)))
+    ));
+    assert!(matches!(
+        iter.next_arg(),
+        Ok(Some(Argument::Subcommand { name, .. })) if name == "add"
+    ));
+    assert!(matches!(
+        iter.next_arg(),
+        Ok(Some(Argument::Custom(Arg::Add(AddArg::Force))))
+    ));
+    assert!(matches!(iter.next_arg(), Ok(Some(Argument::Help))));
+    assert!(iter.help().contains("--force"));
+    assert!(matches!(iter.next_arg(), Ok(None)));
+}
+
+#[test]
+fn subcommands_in_help() {
+    let help = Arg::help("git");
+    let subcommands = help.split_once("\nSubcommands:\n").unwrap().1;
+    assert!(subcommands
+        .starts_with("  add               Add files\n  rm                Remove files\n"));
+}
+
+#[test]
+fn arg_iterator_subcommand_not_first() {
+    let mut iter = ArgIterator::<Arg>::from_args(["git", "foo", "add", "-f"]);
+    assert!(matches!(iter.next(), Some(Ok(Argument::Positional(name))) if name == "foo"));
+    assert!(matches!(iter.next(), Some(Ok(Argument::Positional(name))) if name == "add"));
+    assert!(iter.next().unwrap().is_err());
+}